@@ -0,0 +1,196 @@
+//! Command aliases and macros.
+//!
+//! An alias replaces the first word of the submitted input with its expansion, e.g.
+//! `r` with `config restart`, keeping the rest of the input after it. An expansion
+//! of several commands separated by `;` is a macro: `read_input()` returns its
+//! commands one after another.
+
+use std::fmt;
+
+/// Error returned when an alias cannot be defined.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AliasError {
+    /// The alias name
+    pub name: String,
+    /// Why it was rejected
+    pub reason: &'static str,
+}
+
+impl fmt::Display for AliasError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid alias '{}': {}", self.name, self.reason)
+    }
+}
+
+impl std::error::Error for AliasError {}
+
+/// Aliases by name, in the order they were defined.
+///
+/// # Examples
+///
+/// ```
+/// use daemon_console_lite::alias::Aliases;
+///
+/// let mut aliases = Aliases::new();
+/// aliases.define("r", "config restart").unwrap();
+/// aliases.define("deploy", "config stop; config set port 80; r").unwrap();
+///
+/// assert_eq!(aliases.expand("r now"), ["config restart now"]);
+/// assert_eq!(
+///     aliases.expand("deploy"),
+///     ["config stop", "config set port 80", "config restart"]
+/// );
+/// assert_eq!(aliases.expand("version"), ["version"]);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Aliases {
+    aliases: Vec<(String, String)>,
+}
+
+impl Aliases {
+    /// Creates an empty alias table.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Defines an alias, returning the expansion it replaces.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `AliasError` if the name is not a single word or the expansion
+    /// contains no command.
+    pub fn define(&mut self, name: &str, expansion: &str) -> Result<Option<String>, AliasError> {
+        let error = |reason| AliasError {
+            name: name.to_string(),
+            reason,
+        };
+        if name.is_empty() || name.contains(|c: char| c.is_whitespace() || "\"';".contains(c)) {
+            return Err(error("the name must be a single word"));
+        }
+        if split_commands(expansion).is_empty() {
+            return Err(error("the expansion is empty"));
+        }
+
+        let expansion = expansion.trim().to_string();
+        match self.aliases.iter_mut().find(|(n, _)| n == name) {
+            Some((_, existing)) => Ok(Some(std::mem::replace(existing, expansion))),
+            None => {
+                self.aliases.push((name.to_string(), expansion));
+                Ok(None)
+            }
+        }
+    }
+
+    /// Removes an alias, returning its expansion.
+    pub fn remove(&mut self, name: &str) -> Option<String> {
+        let index = self.aliases.iter().position(|(n, _)| n == name)?;
+        Some(self.aliases.remove(index).1)
+    }
+
+    /// Returns the expansion of an alias.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.aliases
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, expansion)| expansion.as_str())
+    }
+
+    /// Returns the aliases as (name, expansion) pairs.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.aliases
+            .iter()
+            .map(|(name, expansion)| (name.as_str(), expansion.as_str()))
+    }
+
+    /// Returns the number of aliases.
+    pub fn len(&self) -> usize {
+        self.aliases.len()
+    }
+
+    /// Returns whether no alias is defined.
+    pub fn is_empty(&self) -> bool {
+        self.aliases.is_empty()
+    }
+
+    /// Expands the input into the commands to run.
+    ///
+    /// Input not starting with an alias is returned unchanged. The rest of the input
+    /// is appended as typed to the last command of the expansion, so a `;` in it
+    /// doesn't start a new command. Commands of the expansion are expanded in turn,
+    /// except for an alias inside its own expansion.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daemon_console_lite::alias::Aliases;
+    ///
+    /// let mut aliases = Aliases::new();
+    /// aliases.define("e", "echo").unwrap();
+    /// assert_eq!(aliases.expand("e a;b"), ["echo a;b"]);
+    /// ```
+    pub fn expand(&self, input: &str) -> Vec<String> {
+        let mut commands = Vec::new();
+        self.expand_into(input, &mut Vec::new(), &mut commands);
+        commands
+    }
+
+    fn expand_into<'a>(&'a self, input: &str, active: &mut Vec<&'a str>, out: &mut Vec<String>) {
+        let trimmed = input.trim_start();
+        let name_end = trimmed.find(char::is_whitespace).unwrap_or(trimmed.len());
+        let (name, rest) = trimmed.split_at(name_end);
+
+        let alias = self.aliases.iter().find(|(n, _)| n == name);
+        let Some((name, expansion)) = alias.filter(|(n, _)| !active.contains(&n.as_str())) else {
+            out.push(input.to_string());
+            return;
+        };
+        // Only the alias body is split, `;` in the user's arguments is kept as typed
+        let mut commands = split_commands(expansion);
+        if let Some(last) = commands.last_mut() {
+            last.push_str(rest.trim_end());
+        }
+        active.push(name);
+        for command in commands {
+            self.expand_into(&command, active, out);
+        }
+        active.pop();
+    }
+}
+
+/// Splits text into commands at `;` outside quotes, dropping empty ones.
+///
+/// # Examples
+///
+/// ```
+/// use daemon_console_lite::alias::split_commands;
+///
+/// assert_eq!(
+///     split_commands("config stop; echo \"a;b\";; config start"),
+///     ["config stop", "echo \"a;b\"", "config start"]
+/// );
+/// ```
+pub fn split_commands(text: &str) -> Vec<String> {
+    let mut commands = Vec::new();
+    let mut current = String::new();
+    let mut quote = None;
+    for c in text.chars() {
+        match c {
+            ';' if quote.is_none() => commands.push(std::mem::take(&mut current)),
+            '"' | '\'' if quote.is_none() => {
+                quote = Some(c);
+                current.push(c);
+            }
+            c if Some(c) == quote => {
+                quote = None;
+                current.push(c);
+            }
+            c => current.push(c),
+        }
+    }
+    commands.push(current);
+    commands
+        .into_iter()
+        .map(|command| command.trim().to_string())
+        .filter(|command| !command.is_empty())
+        .collect()
+}
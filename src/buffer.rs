@@ -0,0 +1,335 @@
+//! The input line buffer.
+//!
+//! `LineBuffer` keeps the input text together with the cursor, stored as a byte
+//! offset that always sits on a grapheme cluster boundary. Typing, deleting and
+//! moving by one character only look at the text around the cursor, so editing a
+//! long input doesn't rebuild it on every key press. The editing helpers in
+//! `editor`, `vi` and `multiline` work with character indices, so the buffer keeps
+//! the cursor's character index up to date as well.
+
+use crate::grapheme;
+use std::ops::Range;
+use unicode_segmentation::GraphemeCursor;
+
+/// Input text with a cursor on a grapheme cluster boundary.
+///
+/// # Examples
+///
+/// ```
+/// use daemon_console_lite::buffer::LineBuffer;
+///
+/// let mut buffer = LineBuffer::new();
+/// buffer.insert_str("cafe\u{301}");
+/// buffer.insert_char('!');
+/// assert_eq!(buffer.as_str(), "cafe\u{301}!");
+///
+/// // The accented "e" is one character for the editor
+/// buffer.move_left();
+/// buffer.delete_backward();
+/// assert_eq!(buffer.as_str(), "caf!");
+/// assert_eq!(buffer.char_cursor(), 3);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct LineBuffer {
+    text: String,
+    /// Byte offset of the cursor
+    cursor: usize,
+    /// Character index of the cursor
+    char_cursor: usize,
+    /// Number of changes to the text
+    revision: u64,
+}
+
+impl LineBuffer {
+    /// Creates an empty buffer.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the text.
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    /// Returns the length of the text in bytes.
+    pub fn len(&self) -> usize {
+        self.text.len()
+    }
+
+    /// Returns whether the text is empty.
+    pub fn is_empty(&self) -> bool {
+        self.text.is_empty()
+    }
+
+    /// Returns the cursor as a byte offset.
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    /// Returns a counter that changes whenever the text does.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daemon_console_lite::buffer::LineBuffer;
+    ///
+    /// let mut buffer = LineBuffer::from("config");
+    /// let revision = buffer.revision();
+    /// buffer.move_left();
+    /// assert_eq!(buffer.revision(), revision);
+    /// buffer.delete_forward();
+    /// assert_ne!(buffer.revision(), revision);
+    /// ```
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    /// Moves the cursor to a byte offset, keeping the character index in step.
+    fn move_cursor(&mut self, offset: usize) {
+        if offset >= self.cursor {
+            self.char_cursor += self.text[self.cursor..offset].chars().count();
+        } else {
+            self.char_cursor -= self.text[offset..self.cursor].chars().count();
+        }
+        self.cursor = offset;
+    }
+
+    /// Moves the cursor to a byte offset, clamped to the text and moved back to the
+    /// start of the cluster it falls in.
+    pub fn set_cursor(&mut self, offset: usize) {
+        let mut offset = offset.min(self.text.len());
+        while !self.text.is_char_boundary(offset) {
+            offset -= 1;
+        }
+        let mut boundary = GraphemeCursor::new(offset, self.text.len(), true);
+        if !boundary.is_boundary(&self.text, 0).unwrap_or(true) {
+            offset = boundary
+                .prev_boundary(&self.text, 0)
+                .ok()
+                .flatten()
+                .unwrap_or(0);
+        }
+        self.move_cursor(offset);
+    }
+
+    /// Returns the cursor as a character index.
+    pub fn char_cursor(&self) -> usize {
+        self.char_cursor
+    }
+
+    /// Returns the line and column of the cursor, both as character indices.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daemon_console_lite::buffer::LineBuffer;
+    ///
+    /// let buffer = LineBuffer::from("{\n  \"é\": 80");
+    /// assert_eq!(buffer.line_col(), (1, 9));
+    /// ```
+    pub fn line_col(&self) -> (usize, usize) {
+        let before = &self.text[..self.cursor];
+        let line_start = before.rfind('\n').map_or(0, |newline| newline + 1);
+        let line = before.bytes().filter(|&b| b == b'\n').count();
+        (line, before[line_start..].chars().count())
+    }
+
+    /// Moves the cursor to a character index, like `set_cursor`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daemon_console_lite::buffer::LineBuffer;
+    ///
+    /// let mut buffer = LineBuffer::from("e\u{301}x");
+    /// // Inside the accented "e": back to its start
+    /// buffer.set_char_cursor(1);
+    /// assert_eq!(buffer.cursor(), 0);
+    /// buffer.set_char_cursor(2);
+    /// assert_eq!(buffer.cursor(), 3);
+    /// ```
+    pub fn set_char_cursor(&mut self, char_index: usize) {
+        self.set_cursor(grapheme::byte_offset(&self.text, char_index));
+    }
+
+    /// Replaces the text and moves the cursor to its end.
+    pub fn set_text(&mut self, text: impl Into<String>) {
+        let text = text.into();
+        if text != self.text {
+            self.text = text;
+            self.revision += 1;
+        }
+        self.cursor = self.text.len();
+        self.char_cursor = self.text.chars().count();
+    }
+
+    /// Clears the text.
+    pub fn clear(&mut self) {
+        self.take();
+    }
+
+    /// Takes the text out of the buffer, leaving it empty.
+    pub fn take(&mut self) -> String {
+        if !self.text.is_empty() {
+            self.revision += 1;
+        }
+        self.cursor = 0;
+        self.char_cursor = 0;
+        std::mem::take(&mut self.text)
+    }
+
+    /// Inserts a character at the cursor and moves the cursor after it.
+    pub fn insert_char(&mut self, c: char) {
+        self.text.insert(self.cursor, c);
+        self.cursor += c.len_utf8();
+        self.char_cursor += 1;
+        self.revision += 1;
+    }
+
+    /// Inserts text at the cursor and moves the cursor after it.
+    pub fn insert_str(&mut self, text: &str) {
+        if text.is_empty() {
+            return;
+        }
+        self.text.insert_str(self.cursor, text);
+        self.cursor += text.len();
+        self.char_cursor += text.chars().count();
+        self.revision += 1;
+    }
+
+    /// Removes a byte range and returns it, keeping the cursor on the same text.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is out of bounds or not on character boundaries.
+    pub fn drain(&mut self, range: Range<usize>) -> String {
+        if range.is_empty() {
+            return String::new();
+        }
+        if self.cursor >= range.end {
+            self.char_cursor -= self.text[range.clone()].chars().count();
+            self.cursor -= range.len();
+        } else if self.cursor > range.start {
+            self.move_cursor(range.start);
+        }
+        self.revision += 1;
+        self.text.drain(range).collect()
+    }
+
+    /// Returns the start of the cluster before the cursor.
+    fn prev_boundary(&self) -> Option<usize> {
+        GraphemeCursor::new(self.cursor, self.text.len(), true)
+            .prev_boundary(&self.text, 0)
+            .ok()
+            .flatten()
+    }
+
+    /// Returns the end of the cluster after the cursor.
+    fn next_boundary(&self) -> Option<usize> {
+        GraphemeCursor::new(self.cursor, self.text.len(), true)
+            .next_boundary(&self.text, 0)
+            .ok()
+            .flatten()
+    }
+
+    /// Moves the cursor one cluster to the left, returning whether it moved.
+    pub fn move_left(&mut self) -> bool {
+        match self.prev_boundary() {
+            Some(offset) => {
+                self.move_cursor(offset);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Moves the cursor one cluster to the right, returning whether it moved.
+    pub fn move_right(&mut self) -> bool {
+        match self.next_boundary() {
+            Some(offset) => {
+                self.move_cursor(offset);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Deletes the cluster before the cursor, returning whether there was one.
+    pub fn delete_backward(&mut self) -> bool {
+        match self.prev_boundary() {
+            Some(start) => {
+                self.drain(start..self.cursor);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Deletes the cluster under the cursor, returning whether there was one.
+    pub fn delete_forward(&mut self) -> bool {
+        match self.next_boundary() {
+            Some(end) => {
+                self.drain(self.cursor..end);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Swaps the clusters around the cursor, or the last two at the end of the text,
+    /// and moves the cursor after them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daemon_console_lite::buffer::LineBuffer;
+    ///
+    /// let mut buffer = LineBuffer::from("ab\u{1F1FA}\u{1F1F8}");
+    /// buffer.transpose();
+    /// assert_eq!(buffer.as_str(), "a\u{1F1FA}\u{1F1F8}b");
+    /// ```
+    pub fn transpose(&mut self) {
+        let original = self.cursor;
+        if self.cursor == self.text.len() {
+            self.move_left();
+        }
+        let (Some(start), Some(end)) = (self.prev_boundary(), self.next_boundary()) else {
+            self.move_cursor(original);
+            return;
+        };
+        let right = self.text[self.cursor..end].to_string();
+        self.text.insert_str(start, &right);
+        let moved = right.len();
+        self.text.drain(self.cursor + moved..end + moved);
+        self.cursor = end;
+        self.char_cursor += right.chars().count();
+        self.revision += 1;
+    }
+}
+
+impl PartialEq for LineBuffer {
+    /// Compares the text and the cursor, ignoring the revision.
+    fn eq(&self, other: &Self) -> bool {
+        self.text == other.text && self.cursor == other.cursor
+    }
+}
+
+impl Eq for LineBuffer {}
+
+impl From<&str> for LineBuffer {
+    /// Creates a buffer with the cursor at the end of the text.
+    fn from(text: &str) -> Self {
+        let mut buffer = Self::new();
+        buffer.set_text(text);
+        buffer
+    }
+}
+
+impl From<String> for LineBuffer {
+    /// Creates a buffer with the cursor at the end of the text.
+    fn from(text: String) -> Self {
+        let mut buffer = Self::new();
+        buffer.set_text(text);
+        buffer
+    }
+}
@@ -0,0 +1,956 @@
+//! Declarative commands.
+//!
+//! Commands, their subcommands and arguments are declared once as a tree of
+//! `Command`s. Registering them with `TerminalApp::register_command()` adds them to
+//! the completion tree, and `TerminalApp::dispatch()` routes submitted input to
+//! their handlers, so completion and execution always agree.
+
+use crate::TerminalApp;
+use crate::handle::ConsoleHandle;
+use crate::job::JobFuture;
+use crate::tab::CompletionItem;
+use crossterm::style::{Color, ResetColor, SetForegroundColor};
+use std::fmt;
+use std::future::Future;
+use std::ops::Range;
+use std::sync::Arc;
+use std::time::Duration;
+use unicode_width::UnicodeWidthStr;
+
+/// Runs a command with its parsed arguments.
+pub type CommandHandler = Arc<dyn Fn(&mut TerminalApp, &CommandArgs) + Send + Sync>;
+
+/// Starts a command with its parsed arguments, returning the work to run as a job.
+pub type AsyncCommandHandler = Arc<dyn Fn(ConsoleHandle, CommandArgs) -> JobFuture + Send + Sync>;
+
+/// What an argument accepts.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum ArgKind {
+    /// Any single word (default)
+    #[default]
+    String,
+    /// A whole number
+    Int,
+    /// A number, with or without a fraction
+    Float,
+    /// One of the given words
+    Choice(Vec<String>),
+    /// A duration: a number with a unit out of `ms`, `s`, `m`, `h` and `d`, or
+    /// several like `1h30m`. A bare number is in seconds.
+    Duration,
+    /// A `host:port` address, with IPv6 hosts in brackets
+    HostPort,
+    /// The rest of the input as typed, spaces and quotes included. Only valid as
+    /// the last argument.
+    Rest,
+}
+
+impl ArgKind {
+    /// Parses one word, or the rest of the input for `Rest`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use daemon_console_lite::command::{ArgKind, ArgValue};
+    ///
+    /// assert_eq!(ArgKind::Int.parse("-3"), Some(ArgValue::Int(-3)));
+    /// assert_eq!(ArgKind::Int.parse("abc"), None);
+    /// assert_eq!(ArgKind::Float.parse("2.5"), Some(ArgValue::Float(2.5)));
+    /// assert_eq!(
+    ///     ArgKind::Duration.parse("1m30s"),
+    ///     Some(ArgValue::Duration(Duration::from_secs(90)))
+    /// );
+    /// assert_eq!(
+    ///     ArgKind::Duration.parse("250ms"),
+    ///     Some(ArgValue::Duration(Duration::from_millis(250)))
+    /// );
+    /// assert_eq!(ArgKind::Duration.parse("5x"), None);
+    /// assert_eq!(ArgKind::Duration.parse(""), None);
+    /// assert_eq!(
+    ///     ArgKind::HostPort.parse("[::1]:8080"),
+    ///     Some(ArgValue::HostPort("::1".to_string(), 8080))
+    /// );
+    /// assert_eq!(ArgKind::HostPort.parse("localhost:99999"), None);
+    ///
+    /// let level = ArgKind::Choice(vec!["debug".to_string(), "info".to_string()]);
+    /// assert_eq!(level.parse("info"), Some(ArgValue::String("info".to_string())));
+    /// assert_eq!(level.parse("trace"), None);
+    /// ```
+    pub fn parse(&self, text: &str) -> Option<ArgValue> {
+        match self {
+            ArgKind::String | ArgKind::Rest => Some(ArgValue::String(text.to_string())),
+            ArgKind::Int => text.parse().ok().map(ArgValue::Int),
+            ArgKind::Float => text
+                .parse()
+                .ok()
+                .filter(|value: &f64| value.is_finite())
+                .map(ArgValue::Float),
+            ArgKind::Choice(choices) => choices
+                .iter()
+                .any(|choice| choice == text)
+                .then(|| ArgValue::String(text.to_string())),
+            ArgKind::Duration => parse_duration(text).map(ArgValue::Duration),
+            ArgKind::HostPort => {
+                let (host, port) = text.rsplit_once(':')?;
+                let host = match host.strip_prefix('[') {
+                    Some(bracketed) => bracketed.strip_suffix(']')?,
+                    None if host.contains(':') => return None,
+                    None => host,
+                };
+                let port = port.parse().ok()?;
+                (!host.is_empty()).then(|| ArgValue::HostPort(host.to_string(), port))
+            }
+        }
+    }
+
+    /// Describes what the kind accepts, for usage errors.
+    pub fn expected(&self) -> String {
+        match self {
+            ArgKind::String => "a word".to_string(),
+            ArgKind::Int => "an integer".to_string(),
+            ArgKind::Float => "a number".to_string(),
+            ArgKind::Choice(choices) => format!("one of {}", choices.join(", ")),
+            ArgKind::Duration => "a duration like 30s, 5m or 1h30m".to_string(),
+            ArgKind::HostPort => "an address like localhost:8080".to_string(),
+            ArgKind::Rest => "text".to_string(),
+        }
+    }
+}
+
+/// Parses a duration like `90`, `1.5s`, `250ms` or `1h30m`.
+fn parse_duration(text: &str) -> Option<Duration> {
+    if text.is_empty() {
+        return None;
+    }
+    if let Ok(seconds) = text.parse::<f64>() {
+        return Duration::try_from_secs_f64(seconds).ok();
+    }
+    let mut total = Duration::ZERO;
+    let mut rest = text;
+    while !rest.is_empty() {
+        let number_len = rest
+            .find(|c: char| !c.is_ascii_digit() && c != '.')
+            .unwrap_or(rest.len());
+        let (number, after) = rest.split_at(number_len);
+        let unit_len = after
+            .find(|c: char| !c.is_ascii_alphabetic())
+            .unwrap_or(after.len());
+        let (unit, after) = after.split_at(unit_len);
+        let number: f64 = number.parse().ok()?;
+        let seconds = match unit {
+            "ms" => number / 1000.0,
+            "s" => number,
+            "m" => number * 60.0,
+            "h" => number * 3600.0,
+            "d" => number * 86400.0,
+            _ => return None,
+        };
+        total += Duration::try_from_secs_f64(seconds).ok()?;
+        rest = after;
+    }
+    Some(total)
+}
+
+/// A parsed argument.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ArgValue {
+    /// A word, a choice or the rest of the input
+    String(String),
+    /// A whole number
+    Int(i64),
+    /// A number
+    Float(f64),
+    /// A duration
+    Duration(Duration),
+    /// A host and a port
+    HostPort(String, u16),
+}
+
+/// A positional argument of a command.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Arg {
+    /// Name shown in usage and completion hints
+    pub name: String,
+    /// Optional description
+    pub description: Option<String>,
+    /// What the argument accepts
+    pub kind: ArgKind,
+    /// Whether the argument may be left out. Only arguments after the required
+    /// ones can be optional.
+    pub optional: bool,
+}
+
+impl Arg {
+    /// Creates an argument accepting any word.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            description: None,
+            kind: ArgKind::String,
+            optional: false,
+        }
+    }
+
+    /// Creates an argument accepting a whole number.
+    pub fn int(name: impl Into<String>) -> Self {
+        Self::new(name).kind(ArgKind::Int)
+    }
+
+    /// Creates an argument accepting a number.
+    pub fn float(name: impl Into<String>) -> Self {
+        Self::new(name).kind(ArgKind::Float)
+    }
+
+    /// Creates an argument accepting one of `choices`, which are also offered as
+    /// tab completions.
+    pub fn choice(name: impl Into<String>, choices: &[&str]) -> Self {
+        let choices = choices.iter().map(|choice| choice.to_string()).collect();
+        Self::new(name).kind(ArgKind::Choice(choices))
+    }
+
+    /// Creates an argument accepting a duration, see `ArgKind::Duration`.
+    pub fn duration(name: impl Into<String>) -> Self {
+        Self::new(name).kind(ArgKind::Duration)
+    }
+
+    /// Creates an argument accepting a `host:port` address.
+    pub fn host_port(name: impl Into<String>) -> Self {
+        Self::new(name).kind(ArgKind::HostPort)
+    }
+
+    /// Creates an argument taking the rest of the input.
+    pub fn rest(name: impl Into<String>) -> Self {
+        Self::new(name).kind(ArgKind::Rest)
+    }
+
+    /// Sets the description.
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Sets what the argument accepts.
+    pub fn kind(mut self, kind: ArgKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    /// Makes the argument optional.
+    pub fn optional(mut self) -> Self {
+        self.optional = true;
+        self
+    }
+
+    /// Returns the argument as shown in usage: `<name>`, `[name]` if optional, with
+    /// `...` if it takes the rest of the input.
+    pub fn usage(&self) -> String {
+        let dots = if self.kind == ArgKind::Rest {
+            "..."
+        } else {
+            ""
+        };
+        if self.optional {
+            format!("[{}{}]", self.name, dots)
+        } else {
+            format!("<{}{}>", self.name, dots)
+        }
+    }
+}
+
+/// Why input doesn't match a command's arguments.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UsageError {
+    /// A required argument is missing
+    MissingArg(String),
+    /// An argument doesn't parse as its kind
+    InvalidArg {
+        /// Name of the argument
+        name: String,
+        /// The word given for it
+        value: String,
+        /// What the argument accepts, see `ArgKind::expected()`
+        expected: String,
+    },
+    /// There are more words than arguments
+    UnexpectedArg(String),
+    /// A command that only groups subcommands was run without one
+    MissingSubcommand,
+    /// The word after a command that only groups subcommands names none of them
+    UnknownSubcommand(String),
+    /// No registered command has this name
+    UnknownCommand(String),
+}
+
+impl fmt::Display for UsageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UsageError::MissingArg(name) => write!(f, "Missing argument <{}>", name),
+            UsageError::InvalidArg {
+                name,
+                value,
+                expected,
+            } => write!(
+                f,
+                "Invalid value \"{}\" for <{}>: expected {}",
+                value, name, expected
+            ),
+            UsageError::UnexpectedArg(value) => write!(f, "Unexpected argument \"{}\"", value),
+            UsageError::MissingSubcommand => write!(f, "Missing subcommand"),
+            UsageError::UnknownSubcommand(value) => {
+                write!(f, "Unknown subcommand \"{}\"", value)
+            }
+            UsageError::UnknownCommand(value) => write!(f, "Unknown command \"{}\"", value),
+        }
+    }
+}
+
+impl std::error::Error for UsageError {}
+
+/// Formats a usage error for the log: the error, then the usage in color.
+///
+/// # Examples
+///
+/// ```
+/// use daemon_console_lite::command::{UsageError, usage_error};
+///
+/// let error = UsageError::MissingArg("count".to_string());
+/// let message = usage_error(&error, "add-node <count>");
+/// assert!(message.starts_with("Missing argument <count>\nUsage: "));
+/// assert!(message.contains("add-node <count>"));
+/// ```
+pub fn usage_error(error: &UsageError, usage: &str) -> String {
+    format!(
+        "{}\nUsage: {}{}{}",
+        error,
+        SetForegroundColor(Color::Cyan),
+        usage,
+        ResetColor
+    )
+}
+
+/// A command with its subcommands, arguments and handler.
+///
+/// # Examples
+///
+/// ```
+/// use daemon_console_lite::command::{Arg, Command};
+///
+/// let config = Command::new("config")
+///     .description("Manage the service")
+///     .subcommand(Command::new("start").handler(|app, _| app.info("Starting...")))
+///     .subcommand(
+///         Command::new("set")
+///             .arg(Arg::new("key"))
+///             .arg(Arg::new("value"))
+///             .handler(|app, args| {
+///                 app.info(&format!("{} = {}", args.get(0).unwrap_or(""), args.get(1).unwrap_or("")));
+///             }),
+///     );
+/// assert_eq!(config.usage("config"), "config <start|set>");
+/// ```
+#[derive(Clone)]
+pub struct Command {
+    /// The word that runs the command
+    pub name: String,
+    /// Optional description for completion hints and help
+    pub description: Option<String>,
+    /// Positional arguments
+    pub args: Vec<Arg>,
+    /// Subcommands, matched by the word after the command
+    pub subcommands: Vec<Command>,
+    /// Function run when the command is dispatched
+    pub handler: Option<CommandHandler>,
+    /// Function started as a job when the command is dispatched
+    pub async_handler: Option<AsyncCommandHandler>,
+    /// Example inputs shown by `help <command>`
+    pub examples: Vec<String>,
+}
+
+impl Command {
+    /// Creates a command without a handler.
+    ///
+    /// A command without a handler only groups its subcommands: running it on its
+    /// own prints its usage.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            description: None,
+            args: Vec::new(),
+            subcommands: Vec::new(),
+            handler: None,
+            async_handler: None,
+            examples: Vec::new(),
+        }
+    }
+
+    /// Sets the description shown next to the command in completion hints and help.
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Adds a positional argument.
+    pub fn arg(mut self, arg: Arg) -> Self {
+        self.args.push(arg);
+        self
+    }
+
+    /// Adds a subcommand.
+    pub fn subcommand(mut self, subcommand: Command) -> Self {
+        self.subcommands.push(subcommand);
+        self
+    }
+
+    /// Adds an example input, shown by `help <command>`.
+    pub fn example(mut self, example: impl Into<String>) -> Self {
+        self.examples.push(example.into());
+        self
+    }
+
+    /// Sets the function run when the command is dispatched, replacing an async
+    /// handler.
+    pub fn handler(
+        mut self,
+        handler: impl Fn(&mut TerminalApp, &CommandArgs) + Send + Sync + 'static,
+    ) -> Self {
+        self.handler = Some(Arc::new(handler));
+        self.async_handler = None;
+        self
+    }
+
+    /// Sets an async function run as a job when the command is dispatched,
+    /// replacing a handler.
+    ///
+    /// The prompt stays live while the job runs. It logs through the
+    /// `ConsoleHandle` it gets, shows up in `jobs` and is cancelled by Ctrl+C, see
+    /// `TerminalApp::enable_job_commands()`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daemon_console_lite::command::{Arg, Command};
+    ///
+    /// let wait = Command::new("wait")
+    ///     .arg(Arg::duration("time"))
+    ///     .async_handler(|handle, args| async move {
+    ///         let time = args.duration("time").unwrap_or_default();
+    ///         tokio::time::sleep(time).await;
+    ///         handle.info(&format!("Waited {:?}", time));
+    ///     });
+    /// assert!(wait.async_handler.is_some());
+    /// ```
+    pub fn async_handler<F, Fut>(mut self, handler: F) -> Self
+    where
+        F: Fn(ConsoleHandle, CommandArgs) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        self.async_handler = Some(Arc::new(move |handle, args| {
+            Box::pin(handler(handle, args))
+        }));
+        self.handler = None;
+        self
+    }
+
+    /// Returns whether the command has a handler, sync or async.
+    pub fn has_handler(&self) -> bool {
+        self.handler.is_some() || self.async_handler.is_some()
+    }
+
+    /// Returns the subcommand with the given name.
+    pub fn find_subcommand(&self, name: &str) -> Option<&Command> {
+        self.subcommands.iter().find(|sub| sub.name == name)
+    }
+
+    /// Returns a one-line usage, e.g. `config <start|stop>` or `add-node <count>`.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The words naming the command, e.g. `config set`
+    pub fn usage(&self, path: &str) -> String {
+        let mut usage = path.to_string();
+        if !self.subcommands.is_empty() && !self.has_handler() {
+            let names: Vec<&str> = self
+                .subcommands
+                .iter()
+                .map(|sub| sub.name.as_str())
+                .collect();
+            usage.push_str(&format!(" <{}>", names.join("|")));
+        }
+        for arg in &self.args {
+            usage.push(' ');
+            usage.push_str(&arg.usage());
+        }
+        usage
+    }
+
+    /// Parses the input after the command into its arguments' values, in order.
+    ///
+    /// # Arguments
+    ///
+    /// * `rest` - The input after the command, see `CommandArgs::rest`
+    ///
+    /// # Errors
+    ///
+    /// Returns a `UsageError` if an argument is missing or invalid, if there are
+    /// extra words, or if the command only groups subcommands.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daemon_console_lite::command::{Arg, ArgValue, Command, UsageError};
+    ///
+    /// let say = Command::new("say")
+    ///     .arg(Arg::int("times"))
+    ///     .arg(Arg::rest("message").optional())
+    ///     .handler(|_, _| {});
+    ///
+    /// let values = say.parse_args("2 hello  \"world\"").unwrap();
+    /// assert_eq!(values[0], ("times".to_string(), ArgValue::Int(2)));
+    /// assert_eq!(
+    ///     values[1],
+    ///     ("message".to_string(), ArgValue::String("hello  \"world\"".to_string()))
+    /// );
+    /// assert_eq!(say.parse_args("2").unwrap().len(), 1);
+    ///
+    /// assert_eq!(say.parse_args(""), Err(UsageError::MissingArg("times".to_string())));
+    /// assert!(matches!(say.parse_args("abc"), Err(UsageError::InvalidArg { .. })));
+    /// ```
+    pub fn parse_args(&self, rest: &str) -> Result<Vec<(String, ArgValue)>, UsageError> {
+        let mut words = words(rest).into_iter();
+        if !self.has_handler() && !self.subcommands.is_empty() {
+            return Err(match words.next() {
+                Some((_, word)) => UsageError::UnknownSubcommand(word),
+                None => UsageError::MissingSubcommand,
+            });
+        }
+
+        let mut values = Vec::new();
+        for arg in &self.args {
+            let Some((range, word)) = words.next() else {
+                if arg.optional {
+                    continue;
+                }
+                return Err(UsageError::MissingArg(arg.name.clone()));
+            };
+            if arg.kind == ArgKind::Rest {
+                let text = rest[range.start..].trim_end().to_string();
+                values.push((arg.name.clone(), ArgValue::String(text)));
+                return Ok(values);
+            }
+            let value = arg
+                .kind
+                .parse(&word)
+                .ok_or_else(|| UsageError::InvalidArg {
+                    name: arg.name.clone(),
+                    value: word.clone(),
+                    expected: arg.kind.expected(),
+                })?;
+            values.push((arg.name.clone(), value));
+        }
+
+        match words.next() {
+            Some((_, word)) => Err(UsageError::UnexpectedArg(word)),
+            None => Ok(values),
+        }
+    }
+
+    /// Returns the completion items of this command and everything below it, as
+    /// (context, items) pairs for `TabTree::register_completions_advanced()`.
+    ///
+    /// The command itself is completed in `context`, its subcommands in its path,
+    /// and the choices of its first argument, or a hint for it, after its path and a
+    /// space.
+    pub fn completions(&self, context: &str) -> Vec<(String, Vec<CompletionItem>)> {
+        let mut item = CompletionItem::new(&self.name);
+        if let Some(description) = &self.description {
+            item = item.with_description(description);
+        }
+        let mut completions = vec![(context.to_string(), vec![item])];
+
+        let path = if context.is_empty() {
+            self.name.clone()
+        } else {
+            format!("{} {}", context, self.name)
+        };
+        if let Some(arg) = self.args.first() {
+            let items = match &arg.kind {
+                ArgKind::Choice(choices) => choices.iter().map(CompletionItem::new).collect(),
+                _ => {
+                    let hint = match &arg.description {
+                        Some(description) => format!("{}: {}", arg.name, description),
+                        None => arg.name.clone(),
+                    };
+                    vec![CompletionItem::new("").with_description(hint)]
+                }
+            };
+            completions.push((format!("{} ", path), items));
+        }
+        for sub in &self.subcommands {
+            completions.extend(sub.completions(&path));
+        }
+        completions
+    }
+}
+
+/// Arguments of a dispatched command.
+///
+/// # Examples
+///
+/// ```
+/// use daemon_console_lite::command::{Arg, Command, CommandRegistry};
+///
+/// let mut registry = CommandRegistry::new();
+/// registry.register(
+///     Command::new("connect")
+///         .arg(Arg::host_port("address"))
+///         .arg(Arg::duration("timeout").optional())
+///         .handler(|_, _| {}),
+/// );
+///
+/// let (command, mut args) = registry.resolve("connect localhost:8080").unwrap();
+/// args.values = command.parse_args(&args.rest).unwrap();
+/// assert_eq!(args.host_port("address"), Some(("localhost", 8080)));
+/// assert_eq!(args.duration("timeout"), None);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CommandArgs {
+    /// The words naming the command, e.g. `["config", "set"]`
+    pub path: Vec<String>,
+    /// The words after the command, with quotes removed
+    pub args: Vec<String>,
+    /// The input after the command, as typed
+    pub rest: String,
+    /// The parsed arguments by name, filled in by `TerminalApp::dispatch()`
+    pub values: Vec<(String, ArgValue)>,
+}
+
+impl CommandArgs {
+    /// Returns the argument at `index`.
+    pub fn get(&self, index: usize) -> Option<&str> {
+        self.args.get(index).map(String::as_str)
+    }
+
+    /// Returns the number of arguments.
+    pub fn len(&self) -> usize {
+        self.args.len()
+    }
+
+    /// Returns whether there are no arguments.
+    pub fn is_empty(&self) -> bool {
+        self.args.is_empty()
+    }
+
+    /// Returns the parsed value of an argument, if it was given.
+    pub fn value(&self, name: &str) -> Option<&ArgValue> {
+        self.values
+            .iter()
+            .find(|(arg, _)| arg == name)
+            .map(|(_, value)| value)
+    }
+
+    /// Returns the value of a word, choice or rest-of-input argument.
+    pub fn string(&self, name: &str) -> Option<&str> {
+        match self.value(name)? {
+            ArgValue::String(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Returns the value of an integer argument.
+    pub fn int(&self, name: &str) -> Option<i64> {
+        match self.value(name)? {
+            ArgValue::Int(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Returns the value of a number argument.
+    pub fn float(&self, name: &str) -> Option<f64> {
+        match self.value(name)? {
+            ArgValue::Float(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Returns the value of a duration argument.
+    pub fn duration(&self, name: &str) -> Option<Duration> {
+        match self.value(name)? {
+            ArgValue::Duration(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Returns the host and port of an address argument.
+    pub fn host_port(&self, name: &str) -> Option<(&str, u16)> {
+        match self.value(name)? {
+            ArgValue::HostPort(host, port) => Some((host, *port)),
+            _ => None,
+        }
+    }
+}
+
+/// The registered commands.
+///
+/// # Examples
+///
+/// ```
+/// use daemon_console_lite::command::{Command, CommandRegistry};
+///
+/// let mut registry = CommandRegistry::new();
+/// registry.register(Command::new("config").subcommand(Command::new("set")));
+///
+/// let (command, args) = registry.resolve("config set port \"80 90\"").unwrap();
+/// assert_eq!(command.name, "set");
+/// assert_eq!(args.path, ["config", "set"]);
+/// assert_eq!(args.args, ["port", "80 90"]);
+/// assert_eq!(args.rest, "port \"80 90\"");
+/// assert!(registry.resolve("version").is_none());
+/// ```
+#[derive(Clone, Default)]
+pub struct CommandRegistry {
+    commands: Vec<Command>,
+}
+
+impl CommandRegistry {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a command, returning the one with the same name it replaces.
+    pub fn register(&mut self, command: Command) -> Option<Command> {
+        match self.commands.iter_mut().find(|c| c.name == command.name) {
+            Some(existing) => Some(std::mem::replace(existing, command)),
+            None => {
+                self.commands.push(command);
+                None
+            }
+        }
+    }
+
+    /// Returns the registered commands.
+    pub fn commands(&self) -> &[Command] {
+        &self.commands
+    }
+
+    /// Returns the command with the given name.
+    pub fn get(&self, name: &str) -> Option<&Command> {
+        self.commands.iter().find(|c| c.name == name)
+    }
+
+    /// Finds the command named by the leading words of `input`, following
+    /// subcommands as deep as they match, and splits off its arguments.
+    ///
+    /// Returns `None` if the first word isn't a registered command. The values of the
+    /// arguments are left empty, see `Command::parse_args()`.
+    pub fn resolve(&self, input: &str) -> Option<(&Command, CommandArgs)> {
+        let words = words(input);
+        let (first_range, first) = words.first()?;
+        let mut command = self.get(first)?;
+        let mut path = vec![first.clone()];
+        let mut rest_start = first_range.end;
+
+        for (range, word) in &words[1..] {
+            let Some(sub) = command.find_subcommand(word) else {
+                break;
+            };
+            command = sub;
+            path.push(word.clone());
+            rest_start = range.end;
+        }
+
+        let args = words[path.len()..]
+            .iter()
+            .map(|(_, word)| word.clone())
+            .collect();
+        let rest = input[rest_start..].trim().to_string();
+        Some((
+            command,
+            CommandArgs {
+                path,
+                args,
+                rest,
+                values: Vec::new(),
+            },
+        ))
+    }
+
+    /// Returns the completion items of every command, see `Command::completions()`.
+    pub fn completions(&self) -> Vec<(String, Vec<CompletionItem>)> {
+        self.commands
+            .iter()
+            .flat_map(|command| command.completions(""))
+            .collect()
+    }
+
+    /// Returns the list of commands with their descriptions, shown by `help`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daemon_console_lite::command::{Command, CommandRegistry};
+    ///
+    /// let mut registry = CommandRegistry::new();
+    /// registry.register(Command::new("version").description("Show the version."));
+    /// registry.register(Command::new("config").description("Manage the service."));
+    ///
+    /// let help = registry.help();
+    /// assert!(help.starts_with("Commands:\n"));
+    /// assert!(help.contains("version"));
+    /// assert!(help.contains("Manage the service."));
+    /// ```
+    pub fn help(&self) -> String {
+        let mut help = String::from("Commands:");
+        let names: Vec<&str> = self.commands.iter().map(|c| c.name.as_str()).collect();
+        let rows = self
+            .commands
+            .iter()
+            .map(|c| c.description.as_deref().unwrap_or(""));
+        push_table(&mut help, names.into_iter().zip(rows));
+        help.push_str("\nType \"help <command>\" for details.");
+        help
+    }
+
+    /// Returns the details of the command named by `path`, shown by
+    /// `help <command>`: its usage, arguments, subcommands and examples.
+    ///
+    /// # Errors
+    ///
+    /// Returns `UsageError::UnknownCommand` if `path` names no registered command.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daemon_console_lite::command::{Arg, Command, CommandRegistry};
+    ///
+    /// let mut registry = CommandRegistry::new();
+    /// registry.register(
+    ///     Command::new("add-node")
+    ///         .description("Add nodes.")
+    ///         .arg(Arg::int("count").description("Number of nodes"))
+    ///         .example("add-node 3"),
+    /// );
+    ///
+    /// let help = registry.command_help("add-node").unwrap();
+    /// assert!(help.contains("add-node <count>"));
+    /// assert!(help.contains("Number of nodes (an integer)"));
+    /// assert!(help.contains("add-node 3"));
+    /// assert!(registry.command_help("add-node 3").is_err());
+    /// ```
+    pub fn command_help(&self, path: &str) -> Result<String, UsageError> {
+        let unknown = || UsageError::UnknownCommand(path.trim().to_string());
+        let (command, args) = self.resolve(path).ok_or_else(unknown)?;
+        if !args.is_empty() {
+            return Err(unknown());
+        }
+        let path = args.path.join(" ");
+
+        let mut help = format!("{}{}{}", SetForegroundColor(Color::Cyan), path, ResetColor);
+        if let Some(description) = &command.description {
+            help.push_str(&format!(" - {}", description));
+        }
+        help.push_str(&format!("\nUsage: {}", command.usage(&path)));
+
+        if !command.args.is_empty() {
+            help.push_str("\nArguments:");
+            let names: Vec<String> = command.args.iter().map(Arg::usage).collect();
+            let rows: Vec<String> = command
+                .args
+                .iter()
+                .map(|arg| match &arg.description {
+                    Some(description) => format!("{} ({})", description, arg.kind.expected()),
+                    None => arg.kind.expected(),
+                })
+                .collect();
+            push_table(
+                &mut help,
+                names
+                    .iter()
+                    .map(String::as_str)
+                    .zip(rows.iter().map(String::as_str)),
+            );
+        }
+        if !command.subcommands.is_empty() {
+            help.push_str("\nSubcommands:");
+            let names: Vec<&str> = command
+                .subcommands
+                .iter()
+                .map(|c| c.name.as_str())
+                .collect();
+            let rows = command
+                .subcommands
+                .iter()
+                .map(|c| c.description.as_deref().unwrap_or(""));
+            push_table(&mut help, names.into_iter().zip(rows));
+        }
+        if !command.examples.is_empty() {
+            help.push_str("\nExamples:");
+            for example in &command.examples {
+                help.push_str(&format!("\n  {}", example));
+            }
+        }
+        Ok(help)
+    }
+}
+
+/// Appends indented `name  text` lines with the texts lined up.
+fn push_table<'a>(out: &mut String, rows: impl Iterator<Item = (&'a str, &'a str)> + Clone) {
+    let width = rows
+        .clone()
+        .map(|(name, _)| name.width())
+        .max()
+        .unwrap_or(0);
+    for (name, text) in rows {
+        out.push_str(&format!(
+            "\n  {}{}{}",
+            SetForegroundColor(Color::Cyan),
+            name,
+            ResetColor
+        ));
+        if !text.is_empty() {
+            out.push_str(&" ".repeat(width - name.width() + 2));
+            out.push_str(text);
+        }
+    }
+}
+
+/// Splits input into whitespace-separated words with their byte ranges.
+///
+/// Single or double quotes group words and are removed, and a backslash escapes the
+/// next character inside quotes. An unclosed quote runs to the end of the input.
+fn words(input: &str) -> Vec<(Range<usize>, String)> {
+    let mut words = Vec::new();
+    let mut chars = input.char_indices().peekable();
+    while let Some(&(start, c)) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+            continue;
+        }
+        let mut word = String::new();
+        let mut end = input.len();
+        let mut quote = None;
+        let mut escaped = false;
+        while let Some(&(idx, next)) = chars.peek() {
+            if quote.is_none() && next.is_whitespace() {
+                end = idx;
+                break;
+            }
+            chars.next();
+            if escaped {
+                word.push(next);
+                escaped = false;
+            } else if quote.is_some() && next == '\\' {
+                escaped = true;
+            } else if Some(next) == quote {
+                quote = None;
+            } else if quote.is_none() && (next == '"' || next == '\'') {
+                quote = Some(next);
+            } else {
+                word.push(next);
+            }
+        }
+        words.push((start..end, word));
+    }
+    words
+}
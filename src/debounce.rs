@@ -0,0 +1,137 @@
+//! Suppression of duplicated key events.
+//!
+//! Some Windows consoles report a single key press twice in quick succession. The
+//! `KeyDebouncer` drops a press that exactly repeats the previous one within a short
+//! window, while letting through everything a person can actually type: double
+//! letters, held keys (both as `Repeat` events and as repeated presses) and a double
+//! Ctrl+C. A release of the key always ends the duplicate window.
+
+use crossterm::event::{KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use std::time::{Duration, Instant};
+
+/// Default duplicate window on Windows, well below human typing and key repeat speed.
+#[cfg(windows)]
+const DEFAULT_WINDOW: Duration = Duration::from_millis(15);
+
+/// Drops duplicated key presses.
+///
+/// With a zero window nothing is ever dropped. `KeyDebouncer::default()` only enables
+/// the filter on Windows, where the duplicates come from.
+///
+/// # Examples
+///
+/// Double letters typed by a person are far enough apart to get through:
+///
+/// ```
+/// use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+/// use daemon_console_lite::debounce::KeyDebouncer;
+/// use std::time::{Duration, Instant};
+///
+/// let mut debouncer = KeyDebouncer::new(Duration::from_millis(15));
+/// let l = KeyEvent::new(KeyCode::Char('l'), KeyModifiers::NONE);
+/// let start = Instant::now();
+///
+/// assert!(debouncer.accept(&l, start));
+/// assert!(debouncer.accept(&l, start + Duration::from_millis(80)));
+/// // A duplicate reported right after the press is dropped
+/// assert!(!debouncer.accept(&l, start + Duration::from_millis(81)));
+/// ```
+///
+/// Held keys arrive as `Repeat` events or as presses at the key repeat rate:
+///
+/// ```
+/// use crossterm::event::{KeyCode, KeyEvent, KeyEventKind, KeyEventState, KeyModifiers};
+/// use daemon_console_lite::debounce::KeyDebouncer;
+/// use std::time::{Duration, Instant};
+///
+/// let mut debouncer = KeyDebouncer::new(Duration::from_millis(15));
+/// let press = KeyEvent::new(KeyCode::Backspace, KeyModifiers::NONE);
+/// let repeat = KeyEvent::new_with_kind_and_state(
+///     KeyCode::Backspace,
+///     KeyModifiers::NONE,
+///     KeyEventKind::Repeat,
+///     KeyEventState::NONE,
+/// );
+/// let start = Instant::now();
+///
+/// assert!(debouncer.accept(&press, start));
+/// assert!(debouncer.accept(&repeat, start + Duration::from_millis(1)));
+/// for step in 1..10 {
+///     assert!(debouncer.accept(&press, start + Duration::from_millis(30 * step)));
+/// }
+/// ```
+///
+/// Ctrl+C pressed twice to exit gets through, a duplicated Ctrl+C doesn't exit at once:
+///
+/// ```
+/// use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+/// use daemon_console_lite::debounce::KeyDebouncer;
+/// use std::time::{Duration, Instant};
+///
+/// let mut debouncer = KeyDebouncer::new(Duration::from_millis(15));
+/// let ctrl_c = KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL);
+/// let start = Instant::now();
+///
+/// assert!(debouncer.accept(&ctrl_c, start));
+/// assert!(!debouncer.accept(&ctrl_c, start + Duration::from_millis(2)));
+/// assert!(debouncer.accept(&ctrl_c, start + Duration::from_millis(300)));
+/// ```
+#[derive(Debug, Clone)]
+pub struct KeyDebouncer {
+    window: Duration,
+    last_press: Option<(KeyCode, KeyModifiers, Instant)>,
+}
+
+impl KeyDebouncer {
+    /// Creates a debouncer dropping identical presses less than `window` apart.
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            last_press: None,
+        }
+    }
+
+    /// Creates a debouncer that never drops anything.
+    pub fn disabled() -> Self {
+        Self::new(Duration::ZERO)
+    }
+
+    /// Returns the duplicate window.
+    pub fn window(&self) -> Duration {
+        self.window
+    }
+
+    /// Returns whether a key event received at `now` should be processed.
+    ///
+    /// Release events are never processed, but they end the duplicate window for
+    /// their key.
+    pub fn accept(&mut self, event: &KeyEvent, now: Instant) -> bool {
+        match event.kind {
+            KeyEventKind::Release => {
+                self.last_press = None;
+                false
+            }
+            KeyEventKind::Repeat => true,
+            KeyEventKind::Press => {
+                let duplicate = self.last_press.is_some_and(|(code, modifiers, at)| {
+                    code == event.code
+                        && modifiers == event.modifiers
+                        && now.saturating_duration_since(at) < self.window
+                });
+                if !duplicate {
+                    self.last_press = Some((event.code, event.modifiers, now));
+                }
+                !duplicate
+            }
+        }
+    }
+}
+
+impl Default for KeyDebouncer {
+    fn default() -> Self {
+        #[cfg(windows)]
+        return Self::new(DEFAULT_WINDOW);
+        #[cfg(not(windows))]
+        return Self::disabled();
+    }
+}
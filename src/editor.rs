@@ -0,0 +1,166 @@
+//! Readline-style line editing helpers and the kill ring.
+//!
+//! Positions are character indices on grapheme cluster boundaries, the unit of
+//! `LineBuffer::char_cursor`. Word motions treat a cluster as a single character.
+
+use crate::grapheme;
+use std::collections::VecDeque;
+
+/// Default number of entries kept in the kill ring.
+const DEFAULT_KILL_RING_SIZE: usize = 30;
+
+/// Returns whether a character is part of a word for Alt+B/Alt+F style motions.
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// Finds the start of the word before the cursor, skipping separators first.
+///
+/// # Examples
+///
+/// ```
+/// use daemon_console_lite::editor::word_start_before;
+///
+/// assert_eq!(word_start_before("config set-port", 15), 11);
+/// assert_eq!(word_start_before("config set-port", 11), 7);
+/// ```
+pub fn word_start_before(text: &str, cursor: usize) -> usize {
+    let mut chars = grapheme::lead_chars(text);
+    chars.truncate(cursor);
+    let mut pos = chars.len();
+    while pos > 0 && !is_word_char(chars[pos - 1]) {
+        pos -= 1;
+    }
+    while pos > 0 && is_word_char(chars[pos - 1]) {
+        pos -= 1;
+    }
+    pos
+}
+
+/// Finds the end of the word after the cursor, skipping separators first.
+///
+/// # Examples
+///
+/// ```
+/// use daemon_console_lite::editor::word_end_after;
+///
+/// assert_eq!(word_end_after("config set-port", 0), 6);
+/// assert_eq!(word_end_after("config set-port", 6), 10);
+/// ```
+pub fn word_end_after(text: &str, cursor: usize) -> usize {
+    let chars = grapheme::lead_chars(text);
+    let mut pos = cursor.min(chars.len());
+    while pos < chars.len() && !is_word_char(chars[pos]) {
+        pos += 1;
+    }
+    while pos < chars.len() && is_word_char(chars[pos]) {
+        pos += 1;
+    }
+    pos
+}
+
+/// Finds the start of the whitespace-delimited word before the cursor (Ctrl+W).
+///
+/// # Examples
+///
+/// ```
+/// use daemon_console_lite::editor::big_word_start_before;
+///
+/// assert_eq!(big_word_start_before("config set-port ", 16), 7);
+/// ```
+pub fn big_word_start_before(text: &str, cursor: usize) -> usize {
+    let mut chars = grapheme::lead_chars(text);
+    chars.truncate(cursor);
+    let mut pos = chars.len();
+    while pos > 0 && chars[pos - 1].is_whitespace() {
+        pos -= 1;
+    }
+    while pos > 0 && !chars[pos - 1].is_whitespace() {
+        pos -= 1;
+    }
+    pos
+}
+
+/// Direction of a kill, used to join consecutive kills in the right order.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum KillDirection {
+    /// Text before the cursor was killed (Ctrl+W, Ctrl+U)
+    Backward,
+    /// Text after the cursor was killed (Ctrl+K, Alt+D)
+    Forward,
+}
+
+/// Ring of killed text used by yank (Ctrl+Y) and yank-pop (Alt+Y).
+///
+/// # Examples
+///
+/// ```
+/// use daemon_console_lite::editor::{KillDirection, KillRing};
+///
+/// let mut ring = KillRing::new();
+/// ring.kill("port", KillDirection::Forward, false);
+/// ring.kill(" 8080", KillDirection::Forward, true);
+/// ring.kill("config ", KillDirection::Backward, false);
+///
+/// assert_eq!(ring.yank(), Some("config "));
+/// assert_eq!(ring.rotate(), Some("port 8080"));
+/// ```
+#[derive(Clone, Debug)]
+pub struct KillRing {
+    entries: VecDeque<String>,
+    max_entries: usize,
+    /// Index of the entry returned by the next `yank()`
+    yank_index: usize,
+}
+
+impl KillRing {
+    /// Creates an empty kill ring.
+    pub fn new() -> Self {
+        Self {
+            entries: VecDeque::new(),
+            max_entries: DEFAULT_KILL_RING_SIZE,
+            yank_index: 0,
+        }
+    }
+
+    /// Records killed text.
+    ///
+    /// If `append` is true (the previous action was also a kill), the text is joined
+    /// with the newest entry instead of creating a new one.
+    pub fn kill(&mut self, text: &str, direction: KillDirection, append: bool) {
+        if text.is_empty() {
+            return;
+        }
+        self.yank_index = 0;
+        if append && let Some(newest) = self.entries.front_mut() {
+            match direction {
+                KillDirection::Forward => newest.push_str(text),
+                KillDirection::Backward => newest.insert_str(0, text),
+            }
+            return;
+        }
+        self.entries.push_front(text.to_string());
+        self.entries.truncate(self.max_entries);
+    }
+
+    /// Returns the text to yank, starting from the newest kill.
+    pub fn yank(&mut self) -> Option<&str> {
+        self.yank_index = 0;
+        self.entries.front().map(String::as_str)
+    }
+
+    /// Steps to the next older entry for yank-pop, wrapping around.
+    pub fn rotate(&mut self) -> Option<&str> {
+        if self.entries.is_empty() {
+            return None;
+        }
+        self.yank_index = (self.yank_index + 1) % self.entries.len();
+        self.entries.get(self.yank_index).map(String::as_str)
+    }
+}
+
+impl Default for KillRing {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,143 @@
+//! Grapheme cluster boundaries.
+//!
+//! What shows up on screen as one character may be several `char`s: an emoji joined
+//! with ZWJ, a flag made of two regional indicators, or a letter followed by a
+//! combining accent. The line editor moves over and deletes whole extended grapheme
+//! clusters so these are never split. The helpers take and return character
+//! indices that sit on cluster boundaries; `byte_offset` turns one into an offset
+//! for slicing the text.
+
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+/// Returns the byte offset of the character at `char_index`, or the text length.
+///
+/// # Examples
+///
+/// ```
+/// use daemon_console_lite::grapheme::byte_offset;
+///
+/// assert_eq!(byte_offset("héllo", 2), 3);
+/// assert_eq!(byte_offset("héllo", 10), 6);
+/// ```
+pub fn byte_offset(text: &str, char_index: usize) -> usize {
+    text.char_indices()
+        .nth(char_index)
+        .map_or(text.len(), |(offset, _)| offset)
+}
+
+/// Returns the clusters of `text` with their length in characters and display width.
+///
+/// The width of a cluster is measured as a whole, so a ZWJ sequence is two columns
+/// wide rather than the sum of its parts.
+///
+/// # Examples
+///
+/// ```
+/// use daemon_console_lite::grapheme::clusters;
+///
+/// // "e" with a combining acute accent, then a family emoji
+/// let text = "e\u{301}\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}";
+/// assert_eq!(clusters(text), vec![(2, 1), (5, 2)]);
+/// ```
+pub fn clusters(text: &str) -> Vec<(usize, usize)> {
+    text.graphemes(true)
+        .map(|g| (g.chars().count(), g.width()))
+        .collect()
+}
+
+/// Returns the start of the cluster before `cursor`, or 0.
+///
+/// # Examples
+///
+/// ```
+/// use daemon_console_lite::grapheme::prev_boundary;
+///
+/// // A flag is two regional indicators
+/// let text = "a\u{1F1FA}\u{1F1F8}";
+/// assert_eq!(prev_boundary(text, 3), 1);
+/// assert_eq!(prev_boundary(text, 1), 0);
+/// assert_eq!(prev_boundary(text, 0), 0);
+/// ```
+pub fn prev_boundary(text: &str, cursor: usize) -> usize {
+    let mut start = 0;
+    let mut prev = 0;
+    for g in text.graphemes(true) {
+        if start >= cursor {
+            break;
+        }
+        prev = start;
+        start += g.chars().count();
+    }
+    prev
+}
+
+/// Returns the end of the cluster at `cursor`, or the length of the text.
+///
+/// # Examples
+///
+/// ```
+/// use daemon_console_lite::grapheme::next_boundary;
+///
+/// let text = "cafe\u{301}!";
+/// assert_eq!(next_boundary(text, 3), 5);
+/// assert_eq!(next_boundary(text, 5), 6);
+/// assert_eq!(next_boundary(text, 6), 6);
+/// ```
+pub fn next_boundary(text: &str, cursor: usize) -> usize {
+    let mut end = 0;
+    for g in text.graphemes(true) {
+        end += g.chars().count();
+        if end > cursor {
+            break;
+        }
+    }
+    end
+}
+
+/// Moves a position inside a cluster back to the start of that cluster.
+///
+/// # Examples
+///
+/// ```
+/// use daemon_console_lite::grapheme::snap_to_boundary;
+///
+/// let text = "cafe\u{301}!";
+/// assert_eq!(snap_to_boundary(text, 4), 3);
+/// assert_eq!(snap_to_boundary(text, 5), 5);
+/// assert_eq!(snap_to_boundary(text, 10), 6);
+/// ```
+pub fn snap_to_boundary(text: &str, cursor: usize) -> usize {
+    let mut start = 0;
+    for g in text.graphemes(true) {
+        let end = start + g.chars().count();
+        if end > cursor {
+            return start;
+        }
+        start = end;
+    }
+    start
+}
+
+/// Returns the characters of `text`, each replaced by the first character of its
+/// cluster.
+///
+/// Word motions classify characters one by one. Classifying the lead character
+/// instead gives every character of a cluster the same class, so a motion never
+/// stops inside one.
+///
+/// # Examples
+///
+/// ```
+/// use daemon_console_lite::grapheme::lead_chars;
+///
+/// assert_eq!(lead_chars("e\u{301}x"), vec!['e', 'e', 'x']);
+/// ```
+pub fn lead_chars(text: &str) -> Vec<char> {
+    let mut chars = Vec::with_capacity(text.len());
+    for g in text.graphemes(true) {
+        let lead = g.chars().next().unwrap_or_default();
+        chars.extend(g.chars().map(|_| lead));
+    }
+    chars
+}
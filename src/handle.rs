@@ -0,0 +1,181 @@
+//! Cloneable handle for logging from background tasks.
+//!
+//! Every log method on `TerminalApp` needs `&mut TerminalApp`, which is not available
+//! while the main loop is waiting in `read_input()`. A `ConsoleHandle` sends log lines
+//! through a channel instead; `read_input()` drains the channel and prints them above
+//! the input line.
+
+use crossterm::style::Color;
+use tokio::sync::mpsc::UnboundedSender;
+
+use crate::logger::LogLevel;
+use crate::prompt::Prompt;
+
+/// Messages sent from a `ConsoleHandle` to the owning `TerminalApp`.
+#[derive(Debug)]
+pub(crate) enum ConsoleMessage {
+    /// A log message, formatted by the app when it is printed
+    Log {
+        level: LogLevel,
+        message: String,
+        module_name: Option<String>,
+    },
+    /// A pre-formatted line printed as-is
+    Raw(String),
+    /// Replaces the prompt
+    SetPrompt(Prompt),
+    /// Sets a status bar segment
+    SetStatus {
+        name: String,
+        text: String,
+        color: Option<Color>,
+    },
+    /// Removes a status bar segment
+    RemoveStatus(String),
+    /// Asks `read_input()` to return `None` so the main loop can exit
+    Shutdown,
+    /// A job started by an async command handler has completed, or panicked with
+    /// the given message
+    JobFinished { id: usize, panic: Option<String> },
+}
+
+/// Thread-safe handle used to print log lines from any task or thread.
+///
+/// Obtain one with `TerminalApp::handle()`. Handles are cheap to clone and can be
+/// moved into tokio tasks or std threads.
+///
+/// # Examples
+///
+/// ```
+/// use daemon_console_lite::TerminalApp;
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let app = TerminalApp::new();
+///     let handle = app.handle();
+///
+///     tokio::spawn(async move {
+///         handle.info("Worker started");
+///         handle.warn("Queue is getting long");
+///     })
+///     .await
+///     .unwrap();
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct ConsoleHandle {
+    sender: UnboundedSender<ConsoleMessage>,
+}
+
+impl ConsoleHandle {
+    pub(crate) fn new(sender: UnboundedSender<ConsoleMessage>) -> Self {
+        Self { sender }
+    }
+
+    /// Sends a message to the app.
+    ///
+    /// Messages sent after the app has been dropped are silently discarded.
+    fn send(&self, message: ConsoleMessage) {
+        let _ = self.sender.send(message);
+    }
+
+    /// Log info-level messages.
+    pub fn info(&self, message: &str) {
+        self.logger(LogLevel::Info, message, Some("Stream"));
+    }
+
+    /// Log debug-level messages.
+    pub fn debug(&self, message: &str) {
+        self.logger(LogLevel::Debug, message, Some("Stream"));
+    }
+
+    /// Log warn-level messages.
+    pub fn warn(&self, message: &str) {
+        self.logger(LogLevel::Warn, message, Some("Stream"));
+    }
+
+    /// Log error-level messages.
+    pub fn error(&self, message: &str) {
+        self.logger(LogLevel::Error, message, Some("Stream"));
+    }
+
+    /// Log critical-level messages.
+    pub fn critical(&self, message: &str) {
+        self.logger(LogLevel::Critical, message, Some("Stream"));
+    }
+
+    /// Unified logger method, see `TerminalApp::logger`.
+    ///
+    /// # Arguments
+    ///
+    /// * `level` - The log level (Info, Warn, Error, Debug, Critical)
+    /// * `message` - The message content to be logged
+    /// * `module_name` - The name of the module to associate with the log message
+    ///   (defaults to the app name)
+    pub fn logger(&self, level: LogLevel, message: &str, module_name: Option<&str>) {
+        self.send(ConsoleMessage::Log {
+            level,
+            message: message.to_string(),
+            module_name: module_name.map(str::to_string),
+        });
+    }
+
+    /// Prints a pre-formatted line, e.g. one built with `get_info!`.
+    pub fn print(&self, line: &str) {
+        self.send(ConsoleMessage::Raw(line.to_string()));
+    }
+
+    /// Replaces the prompt, e.g. to show a state like `[maintenance]`.
+    pub fn set_prompt(&self, prompt: impl Into<Prompt>) {
+        self.send(ConsoleMessage::SetPrompt(prompt.into()));
+    }
+
+    /// Sets a status bar segment, adding it if it doesn't exist yet.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daemon_console_lite::TerminalApp;
+    ///
+    /// let mut app = TerminalApp::new();
+    /// let handle = app.handle();
+    ///
+    /// handle.set_status("clients", "3 clients");
+    /// app.drain_messages();
+    /// assert_eq!(app.status_bar().plain_text(), "3 clients");
+    /// ```
+    pub fn set_status(&self, name: &str, text: impl Into<String>) {
+        self.send(ConsoleMessage::SetStatus {
+            name: name.to_string(),
+            text: text.into(),
+            color: None,
+        });
+    }
+
+    /// Sets a status bar segment shown in the given color.
+    pub fn set_status_colored(&self, name: &str, text: impl Into<String>, color: Color) {
+        self.send(ConsoleMessage::SetStatus {
+            name: name.to_string(),
+            text: text.into(),
+            color: Some(color),
+        });
+    }
+
+    /// Removes a status bar segment.
+    pub fn remove_status(&self, name: &str) {
+        self.send(ConsoleMessage::RemoveStatus(name.to_string()));
+    }
+
+    /// Reports that the job with the given number has completed or panicked.
+    pub(crate) fn job_finished(&self, id: usize, panic: Option<String>) {
+        self.send(ConsoleMessage::JobFinished { id, panic });
+    }
+
+    /// Asks the console to stop reading input.
+    ///
+    /// The pending or next `read_input()` call returns `Ok(None)`, just like
+    /// when the user exits with Ctrl+D.
+    pub fn request_shutdown(&self) {
+        self.send(ConsoleMessage::Shutdown);
+    }
+}
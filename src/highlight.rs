@@ -0,0 +1,240 @@
+//! Syntax highlighting of the input line.
+//!
+//! A `Highlighter` turns the input into styled spans, which are applied when the
+//! input is drawn. Highlighting only changes colors, never the text, so cursor
+//! placement and scrolling are unaffected. `DefaultHighlighter` colors commands
+//! known to the completion tree, numbers, quoted strings and flags.
+
+use crate::tab::TabTree;
+use crossterm::style::{Attribute, Color, ResetColor, SetForegroundColor};
+use std::ops::Range;
+
+/// A styled part of the input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HighlightSpan {
+    /// Byte range in the input
+    pub range: Range<usize>,
+    /// Foreground color, or the terminal default
+    pub color: Option<Color>,
+    /// Whether the text is bold
+    pub bold: bool,
+}
+
+impl HighlightSpan {
+    /// Creates a colored span.
+    pub fn colored(range: Range<usize>, color: Color) -> Self {
+        Self {
+            range,
+            color: Some(color),
+            bold: false,
+        }
+    }
+}
+
+/// Styles the input line.
+///
+/// # Examples
+///
+/// ```
+/// use crossterm::style::Color;
+/// use daemon_console_lite::highlight::{HighlightSpan, Highlighter};
+/// use daemon_console_lite::tab::TabTree;
+///
+/// /// Shows `sudo` in red.
+/// struct SudoHighlighter;
+///
+/// impl Highlighter for SudoHighlighter {
+///     fn highlight(&self, input: &str, _tab_tree: Option<&TabTree>) -> Vec<HighlightSpan> {
+///         input
+///             .match_indices("sudo")
+///             .map(|(start, word)| HighlightSpan::colored(start..start + word.len(), Color::Red))
+///             .collect()
+///     }
+/// }
+///
+/// let spans = SudoHighlighter.highlight("sudo reboot", None);
+/// assert_eq!(spans, vec![HighlightSpan::colored(0..4, Color::Red)]);
+/// ```
+pub trait Highlighter: Send + Sync {
+    /// Returns the styled spans of `input`, sorted and not overlapping.
+    ///
+    /// Text outside every span is drawn unstyled. `tab_tree` is the app's completion
+    /// tree, if tab completion is enabled.
+    fn highlight(&self, input: &str, tab_tree: Option<&TabTree>) -> Vec<HighlightSpan>;
+}
+
+/// Highlighter used by `TerminalApp::enable_highlighting()`.
+///
+/// The leading words of the input are looked up in the completion tree, each in
+/// the context of the words before it: known ones are green, an unknown first word
+/// is red. Numbers are magenta, quoted strings yellow and flags (`-v`, `--port`)
+/// cyan.
+///
+/// # Examples
+///
+/// ```
+/// use crossterm::style::Color;
+/// use daemon_console_lite::highlight::{DefaultHighlighter, HighlightSpan, Highlighter};
+/// use daemon_console_lite::tab::TabTree;
+///
+/// let mut tree = TabTree::new();
+/// tree.register_completions("", &["config"]);
+/// tree.register_completions("config", &["set"]);
+///
+/// let spans = DefaultHighlighter.highlight("config set port 80 --force", Some(&tree));
+/// assert_eq!(
+///     spans,
+///     vec![
+///         HighlightSpan::colored(0..6, Color::Green),
+///         HighlightSpan::colored(7..10, Color::Green),
+///         HighlightSpan::colored(16..18, Color::Magenta),
+///         HighlightSpan::colored(19..26, Color::Cyan),
+///     ]
+/// );
+///
+/// let spans = DefaultHighlighter.highlight("cnofig \"a b\"", Some(&tree));
+/// assert_eq!(
+///     spans,
+///     vec![
+///         HighlightSpan::colored(0..6, Color::Red),
+///         HighlightSpan::colored(7..12, Color::Yellow),
+///     ]
+/// );
+/// ```
+#[derive(Debug, Default, Clone, Copy)]
+pub struct DefaultHighlighter;
+
+impl Highlighter for DefaultHighlighter {
+    fn highlight(&self, input: &str, tab_tree: Option<&TabTree>) -> Vec<HighlightSpan> {
+        let mut spans = Vec::new();
+        // Words of the command path so far, while every one of them is known
+        let mut command_path = Some(String::new());
+
+        for range in tokens(input) {
+            let token = &input[range.clone()];
+            let color = if token.starts_with(['"', '\'']) {
+                Some(Color::Yellow)
+            } else if is_flag(token) {
+                Some(Color::Cyan)
+            } else if is_number(token) {
+                Some(Color::Magenta)
+            } else {
+                None
+            };
+            if let Some(color) = color {
+                command_path = None;
+                spans.push(HighlightSpan::colored(range, color));
+                continue;
+            }
+
+            let Some(tree) = tab_tree else { continue };
+            let Some(path) = &mut command_path else {
+                continue;
+            };
+            if tree.is_known(path, token) {
+                spans.push(HighlightSpan::colored(range, Color::Green));
+                if !path.is_empty() {
+                    path.push(' ');
+                }
+                path.push_str(token);
+            } else {
+                if path.is_empty() {
+                    spans.push(HighlightSpan::colored(range, Color::Red));
+                }
+                command_path = None;
+            }
+        }
+        spans
+    }
+}
+
+/// Splits the input into whitespace-separated tokens, keeping quoted strings whole.
+///
+/// An unclosed quote runs to the end of the input.
+fn tokens(input: &str) -> Vec<Range<usize>> {
+    let mut tokens = Vec::new();
+    let mut chars = input.char_indices().peekable();
+    while let Some(&(start, c)) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+            continue;
+        }
+        let mut end = input.len();
+        if c == '"' || c == '\'' {
+            chars.next();
+            let mut escaped = false;
+            for (idx, next) in chars.by_ref() {
+                if escaped {
+                    escaped = false;
+                } else if next == '\\' {
+                    escaped = true;
+                } else if next == c {
+                    end = idx + next.len_utf8();
+                    break;
+                }
+            }
+        } else {
+            while let Some(&(idx, next)) = chars.peek() {
+                if next.is_whitespace() {
+                    end = idx;
+                    break;
+                }
+                chars.next();
+            }
+        }
+        tokens.push(start..end);
+    }
+    tokens
+}
+
+fn is_flag(token: &str) -> bool {
+    let name = token.strip_prefix("--").or_else(|| token.strip_prefix('-'));
+    name.is_some_and(|name| name.starts_with(|c: char| c.is_alphabetic()))
+}
+
+fn is_number(token: &str) -> bool {
+    let digits = token.strip_prefix(['-', '+']).unwrap_or(token);
+    digits.starts_with(|c: char| c.is_ascii_digit()) && token.parse::<f64>().is_ok()
+}
+
+/// Applies spans to a piece of the input and returns it with ANSI styling.
+///
+/// `text` is the part of the input starting at byte `offset`, e.g. the visible part
+/// of a scrolled line. Spans are clipped to it.
+///
+/// # Examples
+///
+/// ```
+/// use crossterm::style::Color;
+/// use daemon_console_lite::highlight::{HighlightSpan, render_spans};
+///
+/// let spans = [HighlightSpan::colored(0..6, Color::Green)];
+/// // Only "fig" of "config" is visible
+/// let rendered = render_spans("fig set", 3, &spans);
+/// assert!(rendered.starts_with("\u{1b}[38;5;10mfig"));
+/// assert!(rendered.ends_with(" set"));
+/// ```
+pub fn render_spans(text: &str, offset: usize, spans: &[HighlightSpan]) -> String {
+    let mut rendered = String::with_capacity(text.len());
+    let mut pos = 0;
+    for span in spans {
+        let start = span.range.start.saturating_sub(offset).max(pos);
+        let end = span.range.end.saturating_sub(offset).min(text.len());
+        if start >= end || !text.is_char_boundary(start) || !text.is_char_boundary(end) {
+            continue;
+        }
+        rendered.push_str(&text[pos..start]);
+        if let Some(color) = span.color {
+            rendered.push_str(&SetForegroundColor(color).to_string());
+        }
+        if span.bold {
+            rendered.push_str(&Attribute::Bold.to_string());
+        }
+        rendered.push_str(&text[start..end]);
+        rendered.push_str(&Attribute::Reset.to_string());
+        rendered.push_str(&ResetColor.to_string());
+        pos = end;
+    }
+    rendered.push_str(&text[pos..]);
+    rendered
+}
@@ -0,0 +1,231 @@
+//! File-backed command history.
+//!
+//! History is stored as one entry per line. Backslashes and newlines inside an entry
+//! are escaped, so multi-line commands survive a round trip. Every access takes a
+//! lock, which keeps the file consistent when several console instances share it.
+//! New entries are appended; once the file has grown well past its limit,
+//! `compact_history()` replaces it with the newest entries.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+/// Escapes an entry so it fits on a single line.
+fn escape_entry(entry: &str) -> String {
+    let mut escaped = String::with_capacity(entry.len());
+    for c in entry.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Reverses `escape_entry`.
+fn unescape_entry(line: &str) -> String {
+    let mut entry = String::with_capacity(line.len());
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            entry.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => entry.push('\n'),
+            Some('r') => entry.push('\r'),
+            Some(other) => entry.push(other),
+            None => entry.push('\\'),
+        }
+    }
+    entry
+}
+
+/// Reads all entries from an already opened history file.
+fn read_entries(file: &mut File) -> io::Result<Vec<String>> {
+    file.seek(SeekFrom::Start(0))?;
+    let mut entries = Vec::new();
+    for line in BufReader::new(file).lines() {
+        let line = line?;
+        if !line.is_empty() {
+            entries.push(unescape_entry(&line));
+        }
+    }
+    Ok(entries)
+}
+
+/// Returns `path` with `suffix` appended to its file name.
+fn sibling_path(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(suffix);
+    path.with_file_name(name)
+}
+
+/// Opens and locks the lock file next to a history file, released when dropped.
+///
+/// Compaction renames a new file over the history file, so the lock is taken on a
+/// separate file that stays in place.
+fn lock_history(path: &Path, exclusive: bool) -> io::Result<File> {
+    let lock = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(sibling_path(path, ".lock"))?;
+    if exclusive {
+        lock.lock()?;
+    } else {
+        lock.lock_shared()?;
+    }
+    Ok(lock)
+}
+
+/// Keeps only the newest `max_entries` items.
+fn cap_entries(entries: &mut Vec<String>, max_entries: usize) {
+    if entries.len() > max_entries {
+        entries.drain(..entries.len() - max_entries);
+    }
+}
+
+/// Loads history entries from a file, oldest first.
+///
+/// A missing file is treated as an empty history. At most `max_entries` of the
+/// newest entries are returned.
+///
+/// # Errors
+///
+/// Returns an error if the file exists but cannot be locked or read.
+///
+/// # Examples
+///
+/// ```
+/// use daemon_console_lite::history::{append_history, load_history};
+///
+/// let path = std::env::temp_dir().join("dcl_doc_load_history.txt");
+/// let _ = std::fs::remove_file(&path);
+///
+/// append_history(&path, "config start").unwrap();
+/// append_history(&path, "config set port 8080").unwrap();
+///
+/// let history = load_history(&path, 1).unwrap();
+/// assert_eq!(history, vec!["config set port 8080".to_string()]);
+/// # std::fs::remove_file(&path).unwrap();
+/// ```
+pub fn load_history(path: &Path, max_entries: usize) -> io::Result<Vec<String>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let _lock = lock_history(path, false)?;
+    let mut file = match File::open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    let mut entries = read_entries(&mut file)?;
+    cap_entries(&mut entries, max_entries);
+    Ok(entries)
+}
+
+/// Appends one entry to a history file, creating it if needed.
+///
+/// Only the end of the file is touched, so appending doesn't get slower as the
+/// history grows. Use `compact_history()` to drop old entries.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be opened, locked or written.
+///
+/// # Examples
+///
+/// ```
+/// use daemon_console_lite::history::{append_history, load_history};
+///
+/// let path = std::env::temp_dir().join("dcl_doc_append_history.txt");
+/// let _ = std::fs::remove_file(&path);
+///
+/// append_history(&path, "config start").unwrap();
+/// append_history(&path, "line one\nline two").unwrap();
+///
+/// let history = load_history(&path, 100).unwrap();
+/// assert_eq!(history, vec!["config start", "line one\nline two"]);
+/// # std::fs::remove_file(&path).unwrap();
+/// ```
+pub fn append_history(path: &Path, entry: &str) -> io::Result<()> {
+    let _lock = lock_history(path, true)?;
+    let mut file = OpenOptions::new()
+        .read(true)
+        .append(true)
+        .create(true)
+        .open(path)?;
+
+    // Make sure a torn last line from a crashed writer doesn't swallow this entry
+    let mut separator = "";
+    if file.metadata()?.len() > 0 {
+        let mut last = [0u8];
+        file.seek(SeekFrom::End(-1))?;
+        file.read_exact(&mut last)?;
+        if last[0] != b'\n' {
+            separator = "\n";
+        }
+    }
+    writeln!(file, "{}{}", separator, escape_entry(entry))?;
+    file.flush()
+}
+
+/// Drops all but the newest `max_entries` entries of a history file.
+///
+/// The kept entries are written to a temporary file in the same directory, which
+/// then replaces the history file, so a crash or a full disk leaves the old file
+/// intact. A file with at most `max_entries` entries is left alone.
+///
+/// # Returns
+///
+/// The number of entries left in the file.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be locked, read or replaced.
+///
+/// # Examples
+///
+/// ```
+/// use daemon_console_lite::history::{append_history, compact_history, load_history};
+///
+/// let path = std::env::temp_dir().join("dcl_doc_compact_history.txt");
+/// let _ = std::fs::remove_file(&path);
+///
+/// for i in 0..5 {
+///     append_history(&path, &format!("add-node {}", i)).unwrap();
+/// }
+/// assert_eq!(compact_history(&path, 3).unwrap(), 3);
+///
+/// let history = load_history(&path, 100).unwrap();
+/// assert_eq!(history, vec!["add-node 2", "add-node 3", "add-node 4"]);
+/// # std::fs::remove_file(&path).unwrap();
+/// ```
+pub fn compact_history(path: &Path, max_entries: usize) -> io::Result<usize> {
+    let _lock = lock_history(path, true)?;
+    let mut entries = match File::open(path) {
+        Ok(mut file) => read_entries(&mut file)?,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(0),
+        Err(e) => return Err(e),
+    };
+    if entries.len() <= max_entries {
+        return Ok(entries.len());
+    }
+    cap_entries(&mut entries, max_entries);
+
+    let mut rewritten = String::new();
+    for entry in &entries {
+        rewritten.push_str(&escape_entry(entry));
+        rewritten.push('\n');
+    }
+    let temp_path = sibling_path(path, ".tmp");
+    let mut temp = File::create(&temp_path)?;
+    temp.write_all(rewritten.as_bytes())?;
+    temp.sync_all()?;
+    drop(temp);
+    fs::rename(&temp_path, path)?;
+    Ok(entries.len())
+}
@@ -0,0 +1,159 @@
+//! Commands running in the background.
+//!
+//! A command with an async handler runs as a tokio task, so the prompt stays live
+//! while it works. Each running command is a `Job` with a number, shown by the
+//! `jobs` command. Ctrl+C on an empty input line cancels the newest job. A job
+//! that panics is reported as an error.
+
+use crate::handle::ConsoleHandle;
+use std::any::Any;
+use std::future::Future;
+use std::pin::Pin;
+use std::time::{Duration, Instant};
+use tokio::task::AbortHandle;
+
+/// Future returned by an async command handler.
+pub type JobFuture = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+/// A command running as a tokio task.
+#[derive(Debug)]
+pub struct Job {
+    /// Number of the job, counting from 1
+    pub id: usize,
+    /// The input that started the job
+    pub input: String,
+    /// When the job was started
+    pub started: Instant,
+    task: AbortHandle,
+}
+
+impl Job {
+    /// Returns how long the job has been running.
+    pub fn elapsed(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    /// Returns whether the task has ended, including by panicking.
+    pub fn is_finished(&self) -> bool {
+        self.task.is_finished()
+    }
+}
+
+/// The running jobs, oldest first.
+#[derive(Debug, Default)]
+pub struct Jobs {
+    jobs: Vec<Job>,
+    last_id: usize,
+}
+
+impl Jobs {
+    /// Creates an empty job list.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Spawns a future as a job and returns its number.
+    ///
+    /// When the future completes or panics, the job reports it to the app through
+    /// `handle`, with the panic message if there is one.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside a tokio runtime.
+    pub(crate) fn spawn(&mut self, input: &str, future: JobFuture, handle: ConsoleHandle) -> usize {
+        self.last_id += 1;
+        let id = self.last_id;
+        let task = tokio::spawn(future);
+
+        // Watches the job to report how it ended
+        let abort = task.abort_handle();
+        tokio::spawn(async move {
+            match task.await {
+                Ok(()) => handle.job_finished(id, None),
+                Err(e) if e.is_panic() => {
+                    handle.job_finished(id, Some(panic_message(e.into_panic())));
+                }
+                // A cancelled job was removed when it was cancelled
+                Err(_) => {}
+            }
+        });
+        self.jobs.push(Job {
+            id,
+            input: input.to_string(),
+            started: Instant::now(),
+            task: abort,
+        });
+        id
+    }
+
+    /// Removes a job that reported how it ended.
+    pub(crate) fn finish(&mut self, id: usize) -> Option<Job> {
+        let index = self.jobs.iter().position(|job| job.id == id)?;
+        Some(self.jobs.remove(index))
+    }
+
+    /// Cancels a job, returning it if it was running.
+    ///
+    /// The task is aborted at its next `.await`.
+    pub fn cancel(&mut self, id: usize) -> Option<Job> {
+        // A job that already completed is left to report it
+        let index = self
+            .jobs
+            .iter()
+            .position(|job| job.id == id && !job.is_finished())?;
+        let job = self.jobs.remove(index);
+        job.task.abort();
+        Some(job)
+    }
+
+    /// Cancels the newest running job, returning it.
+    pub fn cancel_newest(&mut self) -> Option<Job> {
+        let id = self.iter().last()?.id;
+        self.cancel(id)
+    }
+
+    /// Returns the running jobs, oldest first.
+    pub fn iter(&self) -> impl Iterator<Item = &Job> {
+        self.jobs.iter().filter(|job| !job.is_finished())
+    }
+
+    /// Returns the number of running jobs.
+    pub fn len(&self) -> usize {
+        self.iter().count()
+    }
+
+    /// Returns whether no job is running.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Returns the message a panic was raised with.
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    match payload.downcast::<String>() {
+        Ok(message) => *message,
+        Err(payload) => payload
+            .downcast_ref::<&str>()
+            .map_or_else(|| "panicked".to_string(), |message| message.to_string()),
+    }
+}
+
+/// Formats a job's running time like `42s` or `3m 05s`.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use daemon_console_lite::job::format_elapsed;
+///
+/// assert_eq!(format_elapsed(Duration::from_secs(42)), "42s");
+/// assert_eq!(format_elapsed(Duration::from_secs(185)), "3m 05s");
+/// ```
+pub fn format_elapsed(elapsed: Duration) -> String {
+    let secs = elapsed.as_secs();
+    if secs < 60 {
+        format!("{}s", secs)
+    } else {
+        format!("{}m {:02}s", secs / 60, secs % 60)
+    }
+}
@@ -0,0 +1,477 @@
+//! Configurable key bindings.
+//!
+//! A `Keymap` maps key sequences to named `EditorAction`s. Sequences can be a single
+//! key press or a multi-key chord such as `Ctrl+X Ctrl+E`. `Keymap::default()` holds
+//! the built-in bindings, which can be overridden or extended at runtime.
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::collections::HashMap;
+use std::fmt;
+
+/// Editor actions that keys can be bound to.
+#[derive(Debug, PartialEq, Eq, Hash, Copy, Clone)]
+pub enum EditorAction {
+    /// Submit the current input (Enter)
+    Submit,
+    /// Insert a line break without submitting (Alt+Enter)
+    InsertNewline,
+    /// Apply the selected completion (Tab)
+    Complete,
+    /// Show or hide the completion hints (Esc)
+    ToggleCompletions,
+    /// Cancel the newest running job, or else clear the input or exit on double
+    /// press (Ctrl+C)
+    Interrupt,
+    /// Delete the character under the cursor, or exit on an empty line (Ctrl+D)
+    EndOfFile,
+    /// Abort the current mode, such as reverse search (Ctrl+G)
+    Abort,
+    /// Previous history entry, or move focus from completions back to the input (Up)
+    HistoryPrevious,
+    /// Next history entry, or move focus to the completions (Down)
+    HistoryNext,
+    /// Start or continue reverse history search (Ctrl+R)
+    ReverseSearch,
+    /// Move one character left, or select the previous completion when focused
+    MoveLeft,
+    /// Move one character right, or select the next completion when focused
+    MoveRight,
+    /// Move to the start of the line
+    MoveToStart,
+    /// Move to the end of the line
+    MoveToEnd,
+    /// Move to the start of the previous word
+    MoveWordLeft,
+    /// Move to the end of the next word
+    MoveWordRight,
+    /// Delete the character before the cursor
+    DeleteBackward,
+    /// Delete the character under the cursor
+    DeleteForward,
+    /// Kill the whitespace-delimited word before the cursor
+    UnixWordRubout,
+    /// Kill the word before the cursor
+    KillWordBackward,
+    /// Kill the word after the cursor
+    KillWordForward,
+    /// Kill from the start of the line to the cursor
+    KillToStart,
+    /// Kill from the cursor to the end of the line
+    KillToEnd,
+    /// Insert the most recent kill
+    Yank,
+    /// Replace the text just yanked with the next older kill
+    YankPop,
+    /// Swap the characters around the cursor
+    TransposeChars,
+    /// Undo the last change to the input line
+    Undo,
+    /// Redo the last undone change
+    Redo,
+}
+
+impl EditorAction {
+    /// Every action, in declaration order.
+    pub const ALL: &'static [EditorAction] = &[
+        EditorAction::Submit,
+        EditorAction::InsertNewline,
+        EditorAction::Complete,
+        EditorAction::ToggleCompletions,
+        EditorAction::Interrupt,
+        EditorAction::EndOfFile,
+        EditorAction::Abort,
+        EditorAction::HistoryPrevious,
+        EditorAction::HistoryNext,
+        EditorAction::ReverseSearch,
+        EditorAction::MoveLeft,
+        EditorAction::MoveRight,
+        EditorAction::MoveToStart,
+        EditorAction::MoveToEnd,
+        EditorAction::MoveWordLeft,
+        EditorAction::MoveWordRight,
+        EditorAction::DeleteBackward,
+        EditorAction::DeleteForward,
+        EditorAction::UnixWordRubout,
+        EditorAction::KillWordBackward,
+        EditorAction::KillWordForward,
+        EditorAction::KillToStart,
+        EditorAction::KillToEnd,
+        EditorAction::Yank,
+        EditorAction::YankPop,
+        EditorAction::TransposeChars,
+        EditorAction::Undo,
+        EditorAction::Redo,
+    ];
+
+    /// Returns the readline-style name of the action, e.g. `"kill-line"`.
+    pub fn name(self) -> &'static str {
+        match self {
+            EditorAction::Submit => "accept-line",
+            EditorAction::InsertNewline => "insert-newline",
+            EditorAction::Complete => "complete",
+            EditorAction::ToggleCompletions => "toggle-completions",
+            EditorAction::Interrupt => "interrupt",
+            EditorAction::EndOfFile => "end-of-file",
+            EditorAction::Abort => "abort",
+            EditorAction::HistoryPrevious => "previous-history",
+            EditorAction::HistoryNext => "next-history",
+            EditorAction::ReverseSearch => "reverse-search-history",
+            EditorAction::MoveLeft => "backward-char",
+            EditorAction::MoveRight => "forward-char",
+            EditorAction::MoveToStart => "beginning-of-line",
+            EditorAction::MoveToEnd => "end-of-line",
+            EditorAction::MoveWordLeft => "backward-word",
+            EditorAction::MoveWordRight => "forward-word",
+            EditorAction::DeleteBackward => "backward-delete-char",
+            EditorAction::DeleteForward => "delete-char",
+            EditorAction::UnixWordRubout => "unix-word-rubout",
+            EditorAction::KillWordBackward => "backward-kill-word",
+            EditorAction::KillWordForward => "kill-word",
+            EditorAction::KillToStart => "unix-line-discard",
+            EditorAction::KillToEnd => "kill-line",
+            EditorAction::Yank => "yank",
+            EditorAction::YankPop => "yank-pop",
+            EditorAction::TransposeChars => "transpose-chars",
+            EditorAction::Undo => "undo",
+            EditorAction::Redo => "redo",
+        }
+    }
+
+    /// Looks up an action by its name.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daemon_console_lite::keymap::EditorAction;
+    ///
+    /// assert_eq!(EditorAction::from_name("kill-line"), Some(EditorAction::KillToEnd));
+    /// assert_eq!(EditorAction::from_name("no-such-action"), None);
+    /// ```
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|action| action.name() == name)
+    }
+}
+
+/// A single key press with modifiers, as used in key bindings.
+///
+/// Shift is dropped for character keys since it is already reflected in the
+/// character itself (`A` instead of `a`).
+#[derive(Debug, PartialEq, Eq, Hash, Copy, Clone)]
+pub struct KeyPress {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl KeyPress {
+    /// Creates a key press, normalizing the modifiers.
+    pub fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        let modifiers = match code {
+            KeyCode::Char(_) => modifiers - KeyModifiers::SHIFT,
+            _ => modifiers,
+        };
+        Self { code, modifiers }
+    }
+
+    /// A key press without modifiers.
+    pub fn plain(code: KeyCode) -> Self {
+        Self::new(code, KeyModifiers::NONE)
+    }
+
+    /// A character key pressed with Ctrl.
+    pub fn ctrl(c: char) -> Self {
+        Self::new(KeyCode::Char(c), KeyModifiers::CONTROL)
+    }
+
+    /// A key pressed with Alt.
+    pub fn alt(code: KeyCode) -> Self {
+        Self::new(code, KeyModifiers::ALT)
+    }
+
+    /// Parses a single key press such as `"ctrl+r"`, `"alt+backspace"` or `"esc"`.
+    ///
+    /// The modifiers are `ctrl` (or `control`), `alt` (or `meta`) and `shift`. Modifier
+    /// and key names are case-insensitive. A single character stands for itself.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crossterm::event::{KeyCode, KeyModifiers};
+    /// use daemon_console_lite::keymap::KeyPress;
+    ///
+    /// let press = KeyPress::parse("Ctrl+R").unwrap();
+    /// assert_eq!(press, KeyPress::new(KeyCode::Char('r'), KeyModifiers::CONTROL));
+    /// assert_eq!(KeyPress::parse("alt+b").unwrap(), KeyPress::alt(KeyCode::Char('b')));
+    /// assert!(KeyPress::parse("hyper+q").is_err());
+    /// assert!(KeyPress::parse("s+x").is_err());
+    /// ```
+    pub fn parse(text: &str) -> Result<Self, KeyParseError> {
+        let error = || KeyParseError {
+            input: text.to_string(),
+        };
+
+        // A trailing "+" after a separator is the plus key itself, e.g. "ctrl++"
+        let (modifier_part, key_part) = if text.len() > 1 && text.ends_with("++") {
+            (&text[..text.len() - 1], "+")
+        } else {
+            match text.rsplit_once('+') {
+                Some((mods, key)) if !key.is_empty() => (mods, key),
+                _ => ("", text),
+            }
+        };
+
+        let mut modifiers = KeyModifiers::NONE;
+        for name in modifier_part.split('+').filter(|name| !name.is_empty()) {
+            modifiers |= match name.to_ascii_lowercase().as_str() {
+                "ctrl" | "control" => KeyModifiers::CONTROL,
+                "alt" | "meta" => KeyModifiers::ALT,
+                "shift" => KeyModifiers::SHIFT,
+                _ => return Err(error()),
+            };
+        }
+
+        let mut chars = key_part.chars();
+        let code = match (chars.next(), chars.next()) {
+            // "ctrl+R" means the same as "ctrl+r", while "shift+r" is "R"
+            (Some(c), None) if !modifier_part.is_empty() && c.is_ascii_alphabetic() => {
+                if modifiers.contains(KeyModifiers::SHIFT) {
+                    KeyCode::Char(c.to_ascii_uppercase())
+                } else {
+                    KeyCode::Char(c.to_ascii_lowercase())
+                }
+            }
+            (Some(c), None) => KeyCode::Char(c),
+            _ => match key_part.to_ascii_lowercase().as_str() {
+                "enter" | "return" => KeyCode::Enter,
+                "tab" => KeyCode::Tab,
+                "backtab" => KeyCode::BackTab,
+                "esc" | "escape" => KeyCode::Esc,
+                "backspace" => KeyCode::Backspace,
+                "delete" | "del" => KeyCode::Delete,
+                "insert" | "ins" => KeyCode::Insert,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "pageup" => KeyCode::PageUp,
+                "pagedown" => KeyCode::PageDown,
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "space" => KeyCode::Char(' '),
+                name => match name.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+                    Some(n) if (1..=24).contains(&n) => KeyCode::F(n),
+                    _ => return Err(error()),
+                },
+            },
+        };
+
+        Ok(Self::new(code, modifiers))
+    }
+
+    /// Parses a whitespace-separated key sequence such as `"ctrl+x ctrl+e"`.
+    pub fn parse_sequence(text: &str) -> Result<Vec<Self>, KeyParseError> {
+        let presses: Vec<Self> = text
+            .split_whitespace()
+            .map(Self::parse)
+            .collect::<Result<_, _>>()?;
+        if presses.is_empty() {
+            return Err(KeyParseError {
+                input: text.to_string(),
+            });
+        }
+        Ok(presses)
+    }
+}
+
+impl From<KeyEvent> for KeyPress {
+    fn from(event: KeyEvent) -> Self {
+        Self::new(event.code, event.modifiers)
+    }
+}
+
+/// Error returned when a key description cannot be parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyParseError {
+    /// The text that failed to parse
+    pub input: String,
+}
+
+impl fmt::Display for KeyParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid key description '{}'", self.input)
+    }
+}
+
+impl std::error::Error for KeyParseError {}
+
+/// Result of looking up a key sequence in a `Keymap`.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum KeymapMatch {
+    /// The sequence is bound to an action
+    Action(EditorAction),
+    /// The sequence is the start of a longer chord, wait for more keys
+    Prefix,
+    /// Nothing is bound to the sequence
+    Unbound,
+}
+
+/// Mapping from key sequences to editor actions.
+///
+/// # Examples
+///
+/// ```
+/// use crossterm::event::KeyCode;
+/// use daemon_console_lite::keymap::{EditorAction, KeyPress, Keymap, KeymapMatch};
+///
+/// let mut keymap = Keymap::default();
+///
+/// // Use F2 instead of Esc to toggle completion hints
+/// keymap.unbind(&[KeyPress::plain(KeyCode::Esc)]);
+/// keymap.bind(&[KeyPress::plain(KeyCode::F(2))], EditorAction::ToggleCompletions);
+///
+/// // Multi-key chord
+/// keymap.bind_str("ctrl+x ctrl+k", EditorAction::KillToStart).unwrap();
+/// assert_eq!(keymap.lookup(&[KeyPress::ctrl('x')]), KeymapMatch::Prefix);
+/// assert_eq!(
+///     keymap.lookup(&[KeyPress::ctrl('x'), KeyPress::ctrl('k')]),
+///     KeymapMatch::Action(EditorAction::KillToStart)
+/// );
+/// ```
+#[derive(Clone, Debug)]
+pub struct Keymap {
+    bindings: HashMap<Vec<KeyPress>, EditorAction>,
+}
+
+impl Keymap {
+    /// Creates a keymap without any bindings.
+    pub fn empty() -> Self {
+        Self {
+            bindings: HashMap::new(),
+        }
+    }
+
+    /// Binds a key sequence to an action, returning the previously bound action.
+    pub fn bind(&mut self, keys: &[KeyPress], action: EditorAction) -> Option<EditorAction> {
+        if keys.is_empty() {
+            return None;
+        }
+        self.bindings.insert(keys.to_vec(), action)
+    }
+
+    /// Binds a key sequence given as text, see `KeyPress::parse_sequence`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the key description cannot be parsed.
+    pub fn bind_str(
+        &mut self,
+        keys: &str,
+        action: EditorAction,
+    ) -> Result<Option<EditorAction>, KeyParseError> {
+        let keys = KeyPress::parse_sequence(keys)?;
+        Ok(self.bind(&keys, action))
+    }
+
+    /// Removes the binding of a key sequence, returning the action it was bound to.
+    pub fn unbind(&mut self, keys: &[KeyPress]) -> Option<EditorAction> {
+        self.bindings.remove(keys)
+    }
+
+    /// Removes every binding to the given action.
+    pub fn unbind_action(&mut self, action: EditorAction) {
+        self.bindings.retain(|_, bound| *bound != action);
+    }
+
+    /// Looks up a key sequence.
+    pub fn lookup(&self, keys: &[KeyPress]) -> KeymapMatch {
+        if let Some(action) = self.bindings.get(keys) {
+            return KeymapMatch::Action(*action);
+        }
+        let is_prefix = self
+            .bindings
+            .keys()
+            .any(|bound| bound.len() > keys.len() && bound.starts_with(keys));
+        if is_prefix {
+            KeymapMatch::Prefix
+        } else {
+            KeymapMatch::Unbound
+        }
+    }
+
+    /// Returns all key sequences bound to an action.
+    pub fn keys_for(&self, action: EditorAction) -> Vec<Vec<KeyPress>> {
+        self.bindings
+            .iter()
+            .filter(|(_, bound)| **bound == action)
+            .map(|(keys, _)| keys.clone())
+            .collect()
+    }
+}
+
+impl Default for Keymap {
+    /// Creates a keymap with the built-in emacs-style bindings.
+    fn default() -> Self {
+        use EditorAction::*;
+
+        let mut keymap = Self::empty();
+        let bindings = [
+            (KeyPress::plain(KeyCode::Enter), Submit),
+            (KeyPress::alt(KeyCode::Enter), InsertNewline),
+            (KeyPress::plain(KeyCode::Tab), Complete),
+            (KeyPress::plain(KeyCode::Esc), ToggleCompletions),
+            (KeyPress::ctrl('c'), Interrupt),
+            (KeyPress::ctrl('d'), EndOfFile),
+            (KeyPress::ctrl('g'), Abort),
+            (KeyPress::plain(KeyCode::Up), HistoryPrevious),
+            (KeyPress::ctrl('p'), HistoryPrevious),
+            (KeyPress::plain(KeyCode::Down), HistoryNext),
+            (KeyPress::ctrl('n'), HistoryNext),
+            (KeyPress::ctrl('r'), ReverseSearch),
+            (KeyPress::plain(KeyCode::Left), MoveLeft),
+            (KeyPress::ctrl('b'), MoveLeft),
+            (KeyPress::plain(KeyCode::Right), MoveRight),
+            (KeyPress::ctrl('f'), MoveRight),
+            (KeyPress::plain(KeyCode::Home), MoveToStart),
+            (KeyPress::ctrl('a'), MoveToStart),
+            (KeyPress::plain(KeyCode::End), MoveToEnd),
+            (KeyPress::ctrl('e'), MoveToEnd),
+            (KeyPress::alt(KeyCode::Char('b')), MoveWordLeft),
+            (KeyPress::alt(KeyCode::Left), MoveWordLeft),
+            (
+                KeyPress::new(KeyCode::Left, KeyModifiers::CONTROL),
+                MoveWordLeft,
+            ),
+            (KeyPress::alt(KeyCode::Char('f')), MoveWordRight),
+            (KeyPress::alt(KeyCode::Right), MoveWordRight),
+            (
+                KeyPress::new(KeyCode::Right, KeyModifiers::CONTROL),
+                MoveWordRight,
+            ),
+            (KeyPress::plain(KeyCode::Backspace), DeleteBackward),
+            (KeyPress::ctrl('h'), DeleteBackward),
+            (KeyPress::plain(KeyCode::Delete), DeleteForward),
+            (KeyPress::ctrl('w'), UnixWordRubout),
+            (KeyPress::alt(KeyCode::Backspace), KillWordBackward),
+            (KeyPress::alt(KeyCode::Char('d')), KillWordForward),
+            (KeyPress::ctrl('u'), KillToStart),
+            (KeyPress::ctrl('k'), KillToEnd),
+            (KeyPress::ctrl('y'), Yank),
+            (KeyPress::alt(KeyCode::Char('y')), YankPop),
+            (KeyPress::ctrl('t'), TransposeChars),
+            (KeyPress::ctrl('_'), Undo),
+            // Most terminals send Ctrl+_ as the same byte as Ctrl+7
+            (KeyPress::ctrl('7'), Undo),
+            (KeyPress::ctrl('z'), Undo),
+            (KeyPress::alt(KeyCode::Char('_')), Redo),
+            // Only distinguishable from Ctrl+Z on terminals reporting Shift
+            (KeyPress::ctrl('Z'), Redo),
+        ];
+        for (key, action) in bindings {
+            keymap.bind(&[key], action);
+        }
+        keymap.bind(&[KeyPress::ctrl('x'), KeyPress::ctrl('u')], Undo);
+        keymap
+    }
+}
@@ -0,0 +1,43 @@
+//! Screen layout modes.
+//!
+//! By default the input line lives inline with the log output: every log line clears
+//! the prompt, prints, and redraws the prompt below it. The fixed layout instead
+//! reserves the bottom rows of the terminal for the input line and completion hints,
+//! and confines log output to a scroll region above them.
+
+use crossterm::Command;
+use std::fmt;
+
+/// Where the input line is drawn relative to log output.
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Default)]
+pub enum LayoutMode {
+    /// The input line follows the log output (default)
+    #[default]
+    Inline,
+    /// The input line and hints are pinned to the bottom rows of the terminal
+    Fixed,
+}
+
+/// Sets the terminal scroll region (DECSTBM), or resets it to the whole screen.
+///
+/// Rows are 0-based and inclusive. Note that terminals move the cursor to the
+/// top-left corner when the region changes.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct SetScrollRegion(pub Option<(u16, u16)>);
+
+impl Command for SetScrollRegion {
+    fn write_ansi(&self, f: &mut impl fmt::Write) -> fmt::Result {
+        match self.0 {
+            Some((top, bottom)) => write!(f, "\x1b[{};{}r", top + 1, bottom + 1),
+            None => write!(f, "\x1b[r"),
+        }
+    }
+
+    #[cfg(windows)]
+    fn execute_winapi(&self) -> std::io::Result<()> {
+        Err(std::io::Error::new(
+            std::io::ErrorKind::Unsupported,
+            "scroll regions require a terminal with ANSI support",
+        ))
+    }
+}
@@ -19,42 +19,119 @@
 
 #![cfg_attr(docsrs, feature(doc_cfg))]
 
+pub mod alias;
+pub mod buffer;
+pub mod command;
+pub mod debounce;
+pub mod editor;
+pub mod grapheme;
+pub mod handle;
+pub mod highlight;
+pub mod history;
+pub mod job;
+pub mod keymap;
+pub mod layout;
 pub mod logger;
+pub mod multiline;
+pub mod paste;
+pub mod prompt;
+pub mod search;
+pub mod status;
+pub mod suggest;
 pub mod tab;
+pub mod undo;
 pub mod utils;
+pub mod vi;
+pub mod viewport;
 
 use crossterm::{
     cursor::{self, RestorePosition, SavePosition},
     event::{
-        self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEvent, KeyEventKind,
-        KeyModifiers, poll,
+        DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste, EnableMouseCapture,
+        Event, EventStream, KeyCode, KeyEvent, KeyModifiers,
     },
-    execute,
+    execute, queue,
     style::{Color, ResetColor, SetForegroundColor},
     terminal::{Clear, ClearType, disable_raw_mode, enable_raw_mode},
 };
+use futures_util::StreamExt;
+use std::collections::VecDeque;
 use std::io::{Stdout, Write, stdout};
+use std::path::PathBuf;
 use std::time::Instant;
-use unicode_width::UnicodeWidthChar;
-
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use unicode_width::UnicodeWidthStr;
+
+use crate::alias::{AliasError, Aliases};
+use crate::buffer::LineBuffer;
+use crate::command::{Arg, Command, CommandRegistry, usage_error};
+use crate::debounce::KeyDebouncer;
+use crate::editor::{KillDirection, KillRing};
+pub use crate::handle::ConsoleHandle;
+use crate::handle::ConsoleMessage;
+use crate::highlight::{DefaultHighlighter, HighlightSpan, Highlighter};
+use crate::job::{Jobs, format_elapsed};
+use crate::keymap::{EditorAction, KeyPress, Keymap, KeymapMatch};
+use crate::layout::{LayoutMode, SetScrollRegion};
 use crate::logger::LogLevel;
-use crate::tab::{CompletionCandidate, TabTree};
+use crate::multiline::ContinuationPredicate;
+use crate::paste::PasteMode;
+pub use crate::prompt::Prompt;
+use crate::search::ReverseSearch;
+use crate::status::{StatusBar, StatusPosition};
+use crate::suggest::AutosuggestMode;
+use crate::tab::{CompletionCandidate, CompletionItem, MatchStrategy, TabTree};
+use crate::undo::{EditKind, Snapshot, UndoStack};
+use crate::utils::truncate_to_width;
+use crate::vi::{EditMode, ViCommand, ViMode, ViState};
+use crate::viewport::scroll_input;
+
+/// What the previous key press did, for joining kills and yank-pop.
+#[derive(Debug, Default, Copy, Clone)]
+enum LastEdit {
+    #[default]
+    Other,
+    /// Text was killed into the kill ring
+    Kill,
+    /// Text was yanked at `start` with `len` characters
+    Yank { start: usize, len: usize },
+}
 
 /// Main terminal application structure managing state and input/output.
 ///
 /// `TerminalApp` provides a complete terminal interface with:
 /// - Command history navigation
-/// - Cursor management
+/// - Cursor management and readline-style editing with a kill ring
+/// - Configurable key bindings via `keymap`
+/// - Optional vi editing mode via `edit_mode`
+/// - Undo/redo of input line changes
+/// - Styled prompt that can change at runtime
+/// - Optional fixed input area below a scrolling log region via `layout_mode`
+/// - Status bar with named segments
+/// - Horizontal scrolling of input wider than the terminal
+/// - Optional multi-line input with a continuation prompt
+/// - Bracketed paste
 /// - Colored logging support
 /// - Non-blocking input handling
 /// - Tab completion support
+/// - Declarative commands dispatched to handlers and fed to tab completion
+/// - Optional built-in `help` command
+/// - Command aliases and macros
+/// - Async commands running as cancellable background jobs
+/// - Logging from background tasks via `ConsoleHandle`
 pub struct TerminalApp {
     /// Handle to stdout for terminal operations
     pub stdout_handle: Stdout,
     /// Command history for up/down navigation
     pub command_history: Vec<String>,
-    /// Current input buffer
-    pub current_input: String,
+    /// Optional file the command history is loaded from and appended to
+    pub history_file: Option<PathBuf>,
+    /// Maximum number of history entries kept in memory and in the history file
+    pub history_max_entries: usize,
+    /// Entries in `history_file` as far as this app knows, to decide when to compact it
+    history_file_entries: usize,
+    /// Current input buffer, with the cursor
+    pub current_input: LineBuffer,
     /// Index in command history (None = not browsing history)
     pub history_index: Option<usize>,
     /// Timestamp of last Ctrl+C press for double-tap detection
@@ -63,30 +140,159 @@ pub struct TerminalApp {
     pub should_exit: bool,
     /// Application name, could be set to any valid text your like.
     pub app_name: String,
+    /// Prompt shown in front of the input, see `set_prompt()`
+    prompt: Prompt,
+    /// Prompt shown in front of every line after the first in multi-line input
+    continuation_prompt: Prompt,
+    /// Decides whether Enter continues the input on a new line, see `enable_multiline()`
+    continuation: Option<ContinuationPredicate>,
+    /// Styles the input when drawn, if syntax highlighting is enabled
+    highlighter: Option<Box<dyn Highlighter>>,
+    /// Commands run by `dispatch()`, see `register_command()`
+    commands: CommandRegistry,
+    /// Whether `init_terminal()` has run and the input line is on screen
+    input_active: bool,
     /// Whether raw mode is enabled
     pub raw_mode_enabled: bool,
+    /// Named segments shown in the status bar
+    status_bar: StatusBar,
+    /// Whether the status bar is drawn above or below the input line
+    pub status_position: StatusPosition,
+    /// Where the input line is drawn, must be set before `init_terminal()`
+    pub layout_mode: LayoutMode,
+    /// Whether the scroll region of the fixed layout is set up
+    fixed_layout_active: bool,
+    /// Terminal size as (columns, rows), updated on resize events
+    terminal_size: (u16, u16),
+    /// First terminal row below the log region in the fixed layout
+    input_area_top: u16,
+    /// Display widths of the rows drawn above the input line in the inline layout
+    rendered_rows_above: Vec<usize>,
+    /// Column the cursor was last placed at on the input line
+    input_cursor_column: u16,
+    /// Row the cursor was last placed at in the fixed layout
+    input_cursor_row: u16,
+    /// First visible character of a horizontally scrolled input line
+    input_scroll_offset: usize,
+    /// First visible line of multi-line input taller than the input area
+    input_line_offset: usize,
     /// Maximum number of tab completion options to display
     pub tab_option_max_count: usize,
     /// Maximum length of each tab completion option (truncated with '...' if exceeded)
     pub tab_option_max_length: usize,
     /// Maximum number of tab completion nodes allowed
     tab_completion_limit: usize,
-    /// Current cursor position in the input line
-    cursor_position: usize,
     /// Temporary storage for current input when browsing history
-    pending_input: Option<String>,
-    /// Cursor position for pending input
-    pending_cursor_position: usize,
+    pending_input: Option<LineBuffer>,
     /// Whether completions are currently hidden (via Esc key)
     completions_hidden: bool,
     /// Whether focus is currently on completions (true) or text input (false)
     focus_on_completions: bool,
-    last_key_event: Option<KeyEvent>,
     tab_tree: Option<TabTree>,
     current_completions: Vec<CompletionCandidate>,
-    hints_rendered: bool,
     selected_completion_index: usize,
     warned_no_tab_tree: bool,
+    /// Sender cloned into every `ConsoleHandle`
+    message_sender: UnboundedSender<ConsoleMessage>,
+    /// Receiver drained by `read_input()`
+    message_receiver: UnboundedReceiver<ConsoleMessage>,
+    /// Async terminal event stream, created on the first `read_input()` call
+    event_stream: Option<EventStream>,
+    /// Key bindings used to turn key presses into editor actions
+    pub keymap: Keymap,
+    /// Editing style: readline-like (default) or modal vi
+    pub edit_mode: EditMode,
+    /// Vi sub-mode and pending operator, used when `edit_mode` is `EditMode::Vi`
+    vi: ViState,
+    /// Active Ctrl+R reverse history search, if any
+    history_search: Option<ReverseSearch>,
+    /// Killed text available to Ctrl+Y / Alt+Y
+    kill_ring: KillRing,
+    /// Undo/redo steps for the current input line
+    undo_stack: UndoStack,
+    /// What the previous key press did
+    last_edit: LastEdit,
+    /// Keys of a multi-key chord typed so far
+    pending_keys: Vec<KeyPress>,
+    /// Submitted inputs not yet returned by `read_input()`
+    submitted_inputs: VecDeque<String>,
+    /// Commands of an expanded alias not yet returned by `read_input()`
+    expanded_inputs: VecDeque<String>,
+    /// Aliases expanded by `read_input()`, see `define_alias()`
+    aliases: Aliases,
+    /// Commands running in the background, see `Command::async_handler()`
+    jobs: Jobs,
+    /// What newlines in pasted text do
+    pub paste_mode: PasteMode,
+    /// Where the greyed-out suggestion after the cursor comes from
+    pub autosuggest_mode: AutosuggestMode,
+    /// Drops key presses the terminal reported twice; `KeyDebouncer::disabled()`
+    /// turns this off
+    pub key_debouncer: KeyDebouncer,
+}
+
+/// Returns whether a key with these modifiers should insert its character.
+///
+/// Ctrl and Alt combinations are editing commands. Ctrl+Alt together is how
+/// AltGr characters arrive on Windows, so those are still text.
+fn is_text_modifiers(modifiers: KeyModifiers) -> bool {
+    let ctrl = modifiers.contains(KeyModifiers::CONTROL);
+    let alt = modifiers.contains(KeyModifiers::ALT);
+    ctrl == alt
+}
+
+/// Builds the visible part of a line that may be wider than `width` columns.
+///
+/// Returns the text to print, with `<` / `>` markers where text is hidden, and the
+/// cursor column within it. `offset` keeps the scroll position between renders.
+/// `spans` style the text, which starts at byte `text_start` of the highlighted input.
+fn scrolled_line(
+    text: &str,
+    cursor: usize,
+    offset: &mut usize,
+    width: usize,
+    spans: &[HighlightSpan],
+    text_start: usize,
+) -> (String, usize) {
+    let view = scroll_input(text, cursor, *offset, width);
+    *offset = view.offset;
+
+    let marker = |m: &str| format!("{}{}{}", SetForegroundColor(Color::DarkGrey), m, ResetColor);
+    let mut line = String::new();
+    if view.hidden_left {
+        line.push_str(&marker("<"));
+    }
+    let visible_start = text_start + grapheme::byte_offset(text, view.offset);
+    line.push_str(&highlight::render_spans(
+        view.visible(text),
+        visible_start,
+        spans,
+    ));
+    if view.hidden_right {
+        line.push_str(&marker(">"));
+    }
+    (line, view.cursor_column)
+}
+
+/// Removes one pair of quotes around the whole text, if any.
+fn unquote(text: &str) -> &str {
+    for quote in ['"', '\''] {
+        if let Some(inner) = text
+            .strip_prefix(quote)
+            .and_then(|rest| rest.strip_suffix(quote))
+            .filter(|inner| !inner.contains(quote))
+        {
+            return inner;
+        }
+    }
+    text
+}
+
+/// Prints as much of `text` as fits in the `remaining` columns of the current row.
+fn print_fitted(out: &mut Stdout, text: &str, remaining: &mut usize) -> std::io::Result<()> {
+    let fitted = truncate_to_width(text, *remaining);
+    *remaining -= fitted.width();
+    queue!(out, crossterm::style::Print(fitted))
 }
 
 impl Default for TerminalApp {
@@ -96,42 +302,117 @@ impl Default for TerminalApp {
 }
 
 impl TerminalApp {
-    /// Removes a character at a specific index in a string.
-    fn remove_char_at(&mut self, index: usize) {
-        let mut chars: Vec<char> = self.current_input.chars().collect();
-        if index < chars.len() {
-            chars.remove(index);
-            self.current_input = chars.into_iter().collect();
-        }
-    }
-
     /// Creates a new terminal application instance with default settings.
     ///
     /// Some attributes are allowed to be modified later, like `app_name`.
     pub fn new() -> Self {
+        let (message_sender, message_receiver) = mpsc::unbounded_channel();
         Self {
             stdout_handle: stdout(),
             command_history: Vec::new(),
-            current_input: String::new(),
+            history_file: None,
+            history_max_entries: 1000,
+            history_file_entries: 0,
+            current_input: LineBuffer::new(),
             history_index: None,
             last_ctrl_c: None,
             should_exit: false,
             app_name: String::from("Daemon Console"),
+            prompt: Prompt::default(),
+            continuation_prompt: Prompt::new().colored("... ", Color::DarkGrey),
+            continuation: None,
+            highlighter: None,
+            commands: CommandRegistry::new(),
+            input_active: false,
             raw_mode_enabled: false,
+            status_bar: StatusBar::new(),
+            status_position: StatusPosition::default(),
+            layout_mode: LayoutMode::default(),
+            fixed_layout_active: false,
+            terminal_size: crossterm::terminal::size().unwrap_or((80, 24)),
+            input_area_top: 0,
+            rendered_rows_above: Vec::new(),
+            input_cursor_column: 0,
+            input_cursor_row: 0,
+            input_scroll_offset: 0,
+            input_line_offset: 0,
             tab_option_max_count: 5,
             tab_option_max_length: 10,
             tab_completion_limit: 10000,
-            cursor_position: 0,
             pending_input: None,
-            pending_cursor_position: 0,
             completions_hidden: false,
             focus_on_completions: false,
-            last_key_event: None,
             tab_tree: None,
             current_completions: Vec::new(),
-            hints_rendered: false,
             selected_completion_index: 0,
             warned_no_tab_tree: false,
+            message_sender,
+            message_receiver,
+            event_stream: None,
+            keymap: Keymap::default(),
+            edit_mode: EditMode::default(),
+            vi: ViState::default(),
+            history_search: None,
+            kill_ring: KillRing::new(),
+            undo_stack: UndoStack::new(),
+            last_edit: LastEdit::Other,
+            pending_keys: Vec::new(),
+            submitted_inputs: VecDeque::new(),
+            expanded_inputs: VecDeque::new(),
+            aliases: Aliases::new(),
+            jobs: Jobs::new(),
+            paste_mode: PasteMode::default(),
+            autosuggest_mode: AutosuggestMode::default(),
+            key_debouncer: KeyDebouncer::default(),
+        }
+    }
+
+    /// Returns a cloneable handle that can log from other tasks or threads.
+    ///
+    /// Messages sent through the handle are printed above the input line while
+    /// `read_input()` is waiting, or by `drain_messages()`.
+    pub fn handle(&self) -> ConsoleHandle {
+        ConsoleHandle::new(self.message_sender.clone())
+    }
+
+    /// Prints all messages currently queued by `ConsoleHandle`s.
+    ///
+    /// `read_input()` does this automatically; call it when logs should appear
+    /// while the app is not waiting for input.
+    pub fn drain_messages(&mut self) {
+        while let Ok(message) = self.message_receiver.try_recv() {
+            self.handle_console_message(message);
+        }
+    }
+
+    /// Prints a single message received from a `ConsoleHandle`.
+    fn handle_console_message(&mut self, message: ConsoleMessage) {
+        match message {
+            ConsoleMessage::Log {
+                level,
+                message,
+                module_name,
+            } => self.logger(level, &message, module_name.as_deref()),
+            ConsoleMessage::Raw(line) => self.print_log_entry(&line),
+            ConsoleMessage::SetPrompt(prompt) => self.set_prompt(prompt),
+            ConsoleMessage::SetStatus { name, text, color } => {
+                self.update_status(|bar| bar.set(&name, text, color))
+            }
+            ConsoleMessage::RemoveStatus(name) => self.remove_status(&name),
+            ConsoleMessage::Shutdown => self.should_exit = true,
+            ConsoleMessage::JobFinished { id, panic } => {
+                if let Some(job) = self.jobs.finish(id) {
+                    match panic {
+                        Some(message) => {
+                            self.error(&format!("[{}] Failed: {}: {}", job.id, job.input, message))
+                        }
+                        None => {
+                            let elapsed = format_elapsed(job.elapsed());
+                            self.info(&format!("[{}] Done: {} ({})", job.id, job.input, elapsed));
+                        }
+                    }
+                }
+            }
         }
     }
 
@@ -139,6 +420,17 @@ impl TerminalApp {
     pub fn enable_tab_completion(&mut self) {
         if self.tab_tree.is_none() {
             self.tab_tree = Some(TabTree::new());
+            // Commands and aliases defined before completion was enabled
+            let completions = self.commands.completions();
+            self.add_command_completions(completions);
+            let aliases: Vec<(String, String)> = self
+                .aliases
+                .iter()
+                .map(|(name, expansion)| (name.to_string(), expansion.to_string()))
+                .collect();
+            for (name, expansion) in aliases {
+                self.add_alias_completion(&name, &expansion);
+            }
         } else {
             self.logger(LogLevel::Warn, "Tab completion is already enabled.", None);
         }
@@ -149,6 +441,11 @@ impl TerminalApp {
         self.tab_tree.is_some()
     }
 
+    /// Returns the completion tree, if tab completion is enabled.
+    pub fn tab_tree(&self) -> Option<&TabTree> {
+        self.tab_tree.as_ref()
+    }
+
     /// Registers completions for a given context.
     ///
     /// # Arguments
@@ -244,6 +541,366 @@ impl TerminalApp {
         }
     }
 
+    /// Registers a command, replacing one with the same name.
+    ///
+    /// The command, its subcommands and a hint for its first argument are added to
+    /// the completion tree if tab completion is enabled, or once it is. The
+    /// completions of a replaced command are removed first, so they always match
+    /// what `dispatch()` runs.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daemon_console_lite::TerminalApp;
+    /// use daemon_console_lite::command::{Arg, Command};
+    ///
+    /// let mut app = TerminalApp::new();
+    /// app.enable_tab_completion();
+    /// app.register_command(
+    ///     Command::new("greet")
+    ///         .description("Say hello")
+    ///         .arg(Arg::new("name"))
+    ///         .handler(|app, args| {
+    ///             app.info(&format!("Hello, {}!", args.get(0).unwrap_or("world")));
+    ///         }),
+    /// );
+    /// assert!(app.commands().get("greet").is_some());
+    ///
+    /// // Registering "config" again drops the completion of its old subcommand
+    /// app.register_command(
+    ///     Command::new("config")
+    ///         .subcommand(Command::new("start"))
+    ///         .subcommand(Command::new("stop")),
+    /// );
+    /// app.register_command(Command::new("config").subcommand(Command::new("start")));
+    /// let tree = app.tab_tree().unwrap();
+    /// assert!(tree.is_known("config", "start"));
+    /// assert!(!tree.is_known("config", "stop"));
+    /// ```
+    pub fn register_command(&mut self, command: Command) {
+        let completions = command.completions("");
+        let replaced = self.commands.register(command);
+        if let (Some(replaced), Some(tree)) = (replaced, &mut self.tab_tree) {
+            for (context, items) in replaced.completions("") {
+                for item in items {
+                    tree.remove_completion(&context, &item.text);
+                }
+            }
+        }
+        if self.tab_tree.is_some() {
+            self.add_command_completions(completions);
+        }
+    }
+
+    /// Returns the registered commands.
+    pub fn commands(&self) -> &CommandRegistry {
+        &self.commands
+    }
+
+    /// Runs the registered command named by the input.
+    ///
+    /// The arguments are parsed first and passed to the handler in
+    /// `CommandArgs::values`. If they don't match the command's declaration, or the
+    /// command only groups subcommands, a usage error is logged instead. An async
+    /// handler is started as a job and this returns right away, see `jobs()`.
+    ///
+    /// # Returns
+    ///
+    /// Whether the input named a registered command. Other input is left to the
+    /// caller, e.g. to report it as unknown.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a command with an async handler is dispatched outside a tokio
+    /// runtime.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use daemon_console_lite::TerminalApp;
+    /// use daemon_console_lite::command::Command;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let mut app = TerminalApp::new();
+    ///     app.register_command(Command::new("exit").handler(|app, _| app.should_exit = true));
+    ///
+    ///     app.init_terminal("Welcome!").await?;
+    ///     while let Some(input) = app.read_input().await? {
+    ///         if !app.dispatch(&input) {
+    ///             app.warn(&format!("Unknown command: {}", input));
+    ///         }
+    ///     }
+    ///     app.shutdown_terminal("Goodbye!").await?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn dispatch(&mut self, input: &str) -> bool {
+        let Some((command, mut args)) = self.commands.resolve(input) else {
+            return false;
+        };
+        match command.parse_args(&args.rest) {
+            Ok(values) => {
+                args.values = values;
+                if let Some(handler) = command.handler.clone() {
+                    handler(self, &args);
+                } else if let Some(handler) = command.async_handler.clone() {
+                    let future = handler(self.handle(), args);
+                    let handle = self.handle();
+                    self.jobs.spawn(input.trim(), future, handle);
+                }
+            }
+            Err(error) => {
+                let usage = command.usage(&args.path.join(" "));
+                self.error(&usage_error(&error, &usage));
+            }
+        }
+        true
+    }
+
+    /// Registers the built-in `help` command.
+    ///
+    /// `help` lists the registered commands with their descriptions, and
+    /// `help <command>` shows the usage, arguments, subcommands and examples of one,
+    /// e.g. `help config set`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daemon_console_lite::TerminalApp;
+    ///
+    /// let mut app = TerminalApp::new();
+    /// app.enable_help();
+    /// assert!(app.commands().get("help").is_some());
+    /// ```
+    pub fn enable_help(&mut self) {
+        self.register_command(
+            Command::new("help")
+                .description("Show commands, or details of one.")
+                .arg(
+                    Arg::rest("command")
+                        .description("Command to show")
+                        .optional(),
+                )
+                .example("help")
+                .example("help config set")
+                .handler(|app, args| {
+                    let help = match args.string("command") {
+                        Some(path) => app.commands.command_help(path),
+                        None => Ok(app.commands.help()),
+                    };
+                    match help {
+                        Ok(help) => app.info(&help),
+                        Err(error) => app.error(&error.to_string()),
+                    }
+                }),
+        );
+    }
+
+    /// Returns the commands running in the background.
+    pub fn jobs(&self) -> &Jobs {
+        &self.jobs
+    }
+
+    /// Cancels a running job, returning whether it was running.
+    pub fn cancel_job(&mut self, id: usize) -> bool {
+        self.jobs.cancel(id).is_some()
+    }
+
+    /// Registers the built-in `jobs` and `cancel` commands.
+    ///
+    /// - `jobs` lists the commands running in the background with their numbers
+    /// - `cancel <id>` cancels one
+    ///
+    /// Ctrl+C on an empty input line cancels the newest job while any is running,
+    /// whether these commands are registered or not.
+    pub fn enable_job_commands(&mut self) {
+        self.register_command(
+            Command::new("jobs")
+                .description("List running commands.")
+                .handler(|app, _| {
+                    // Completed jobs report first, so only running ones are listed
+                    app.drain_messages();
+                    if app.jobs.is_empty() {
+                        app.info("No running jobs.");
+                        return;
+                    }
+                    let list: Vec<String> = app
+                        .jobs
+                        .iter()
+                        .map(|job| {
+                            let elapsed = format_elapsed(job.elapsed());
+                            format!("  [{}] {:>7}  {}", job.id, elapsed, job.input)
+                        })
+                        .collect();
+                    app.info(&format!("Jobs:\n{}", list.join("\n")));
+                }),
+        );
+        self.register_command(
+            Command::new("cancel")
+                .description("Cancel a running command.")
+                .arg(Arg::int("id").description("Job number shown by jobs"))
+                .handler(|app, args| {
+                    let id = args.int("id").unwrap_or_default();
+                    match usize::try_from(id).ok().and_then(|id| app.jobs.cancel(id)) {
+                        Some(job) => app.warn(&format!("[{}] Cancelled: {}", job.id, job.input)),
+                        None => app.error(&format!("No running job {}", id)),
+                    }
+                }),
+        );
+    }
+
+    /// Defines an alias, or redefines an existing one.
+    ///
+    /// Input whose first word is `name` is returned by `read_input()` with that word
+    /// replaced by `expansion`. An expansion of several commands separated by `;` is
+    /// a macro, whose commands `read_input()` returns one by one. The alias is also
+    /// offered as a tab completion, described by its expansion.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `AliasError` if the name is not a single word or the expansion
+    /// contains no command.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daemon_console_lite::TerminalApp;
+    ///
+    /// let mut app = TerminalApp::new();
+    /// app.define_alias("r", "config restart").unwrap();
+    /// app.define_alias("redeploy", "config stop; config start").unwrap();
+    /// assert!(app.define_alias("two words", "version").is_err());
+    /// assert_eq!(app.aliases().expand("r"), ["config restart"]);
+    /// ```
+    pub fn define_alias(&mut self, name: &str, expansion: &str) -> Result<(), AliasError> {
+        if self.aliases.define(name, expansion)?.is_some() {
+            self.remove_alias_completion(name);
+        }
+        let expansion = self.aliases.get(name).unwrap_or(expansion).to_string();
+        self.add_alias_completion(name, &expansion);
+        Ok(())
+    }
+
+    /// Removes an alias, returning whether it was defined.
+    pub fn remove_alias(&mut self, name: &str) -> bool {
+        if self.aliases.remove(name).is_none() {
+            return false;
+        }
+        self.remove_alias_completion(name);
+        true
+    }
+
+    /// Returns the defined aliases.
+    pub fn aliases(&self) -> &Aliases {
+        &self.aliases
+    }
+
+    /// Registers the built-in `alias` and `unalias` commands.
+    ///
+    /// - `alias` lists the aliases
+    /// - `alias <name>` shows one
+    /// - `alias <name> <expansion...>` defines one, e.g. `alias r config restart` or
+    ///   `alias redeploy config stop; config start`
+    /// - `unalias <name>` removes one
+    pub fn enable_alias_command(&mut self) {
+        self.register_command(
+            Command::new("alias")
+                .description("List or define aliases.")
+                .arg(Arg::new("name").optional())
+                .arg(
+                    Arg::rest("expansion")
+                        .description("Commands separated by ';'")
+                        .optional(),
+                )
+                .example("alias r config restart")
+                .example("alias redeploy config stop; config start")
+                .handler(
+                    |app, args| match (args.string("name"), args.string("expansion")) {
+                        (None, _) if app.aliases.is_empty() => app.info("No aliases defined."),
+                        (None, _) => {
+                            let list: Vec<String> = app
+                                .aliases
+                                .iter()
+                                .map(|(name, expansion)| format!("  {} = {}", name, expansion))
+                                .collect();
+                            app.info(&format!("Aliases:\n{}", list.join("\n")));
+                        }
+                        (Some(name), None) => match app.aliases.get(name) {
+                            Some(expansion) => {
+                                let message = format!("{} = {}", name, expansion);
+                                app.info(&message);
+                            }
+                            None => app.error(&format!("Unknown alias \"{}\"", name)),
+                        },
+                        (Some(name), Some(expansion)) => {
+                            let expansion = unquote(expansion);
+                            match app.define_alias(name, expansion) {
+                                Ok(()) => app.info(&format!("{} = {}", name, expansion)),
+                                Err(error) => app.error(&error.to_string()),
+                            }
+                        }
+                    },
+                ),
+        );
+        self.register_command(
+            Command::new("unalias")
+                .description("Remove an alias.")
+                .arg(Arg::new("name"))
+                .handler(|app, args| {
+                    let name = args.string("name").unwrap_or_default();
+                    if app.remove_alias(name) {
+                        app.info(&format!("Removed alias {}", name));
+                    } else {
+                        app.error(&format!("Unknown alias \"{}\"", name));
+                    }
+                }),
+        );
+    }
+
+    /// Adds an alias to the completion tree, described by its expansion.
+    ///
+    /// An alias shadowing a command keeps the command's completion.
+    fn add_alias_completion(&mut self, name: &str, expansion: &str) {
+        if self.tab_tree.is_some() && self.commands.get(name).is_none() {
+            let description = format!("alias: {}", expansion);
+            self.add_tab_completion("", name, Some(&description));
+        }
+    }
+
+    /// Removes an alias from the completion tree, unless a command has its name.
+    fn remove_alias_completion(&mut self, name: &str) {
+        if self.commands.get(name).is_none()
+            && let Some(tree) = &mut self.tab_tree
+        {
+            tree.remove_completion("", name);
+        }
+    }
+
+    /// Adds command completions to the completion tree, within its size limit.
+    fn add_command_completions(&mut self, completions: Vec<(String, Vec<CompletionItem>)>) {
+        let Some(tree) = &mut self.tab_tree else {
+            return;
+        };
+        let count: usize = completions.iter().map(|(_, items)| items.len()).sum();
+        let current_count = tree.count_total_items();
+        if current_count + count > self.tab_completion_limit {
+            self.logger(
+                LogLevel::Warn,
+                &format!(
+                    "Cannot register {} command completions: would exceed limit of {}. Current count: {}",
+                    count, self.tab_completion_limit, current_count
+                ),
+                None,
+            );
+            return;
+        }
+        for (context, items) in completions {
+            // Items already registered by hand are skipped
+            tree.register_completions_advanced(&context, items, MatchStrategy::default());
+        }
+    }
+
     /// Adds a single completion item to an existing context.
     ///
     /// # Arguments
@@ -272,6 +929,8 @@ impl TerminalApp {
 
     /// Initializes the terminal with raw mode and displays startup messages.
     ///
+    /// If `history_file` is set, the command history is loaded from it.
+    ///
     /// # Arguments
     ///
     /// * `startup_message` - Message to display on startup
@@ -289,11 +948,77 @@ impl TerminalApp {
             self.print_startup_message(startup_message).await?;
         }
 
+        self.load_history_file();
+
+        if self.layout_mode == LayoutMode::Fixed {
+            self.setup_fixed_layout()?;
+        }
+
         enable_raw_mode()?;
+        // Not every terminal supports bracketed paste, pasting then falls back to key events
+        let _ = execute!(self.stdout_handle, EnableBracketedPaste);
+        self.input_active = true;
 
         Ok(())
     }
 
+    /// Loads `history_file` into `command_history`, keeping entries added before.
+    fn load_history_file(&mut self) {
+        let Some(path) = self.history_file.clone() else {
+            return;
+        };
+        let loaded = history::compact_history(&path, self.history_max_entries).and_then(|count| {
+            self.history_file_entries = count;
+            history::load_history(&path, self.history_max_entries)
+        });
+        match loaded {
+            Ok(mut entries) => {
+                entries.append(&mut self.command_history);
+                self.command_history = entries;
+                self.cap_history();
+            }
+            Err(e) => self.logger(
+                LogLevel::Warn,
+                &format!("Failed to load history from '{}': {}", path.display(), e),
+                None,
+            ),
+        }
+    }
+
+    /// Adds an entry to `command_history` and appends it to `history_file`.
+    ///
+    /// The file is compacted once it holds twice `history_max_entries`, so most
+    /// entries are a plain append.
+    fn push_history(&mut self, entry: String) {
+        if let Some(path) = self.history_file.clone() {
+            let saved = history::append_history(&path, &entry).and_then(|()| {
+                self.history_file_entries += 1;
+                if self.history_file_entries > self.history_max_entries.saturating_mul(2) {
+                    self.history_file_entries =
+                        history::compact_history(&path, self.history_max_entries)?;
+                }
+                Ok(())
+            });
+            if let Err(e) = saved {
+                self.logger(
+                    LogLevel::Warn,
+                    &format!("Failed to save history to '{}': {}", path.display(), e),
+                    None,
+                );
+            }
+        }
+        self.command_history.push(entry);
+        self.cap_history();
+    }
+
+    /// Drops the oldest history entries beyond `history_max_entries`.
+    fn cap_history(&mut self) {
+        if self.command_history.len() > self.history_max_entries {
+            let excess = self.command_history.len() - self.history_max_entries;
+            self.command_history.drain(..excess);
+        }
+    }
+
     /// Sets up the terminal in raw mode and enables mouse capture
     ///
     /// Raw mode is disabled by default to allow text selection.
@@ -312,6 +1037,95 @@ impl TerminalApp {
         Ok(())
     }
 
+    /// Reserves the bottom rows for the input area and confines log output to a
+    /// scroll region above them.
+    ///
+    /// Existing output is scrolled up if it would be covered by the input area. The
+    /// log cursor is kept as the terminal's saved cursor position, so it survives
+    /// while the cursor sits in the input area. Stays in the inline layout if the
+    /// terminal is too small to hold both areas.
+    fn setup_fixed_layout(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        let reserved = self.reserved_rows();
+        self.terminal_size = crossterm::terminal::size()?;
+        let rows = self.terminal_size.1;
+        if rows <= reserved {
+            return Ok(());
+        }
+        let region_bottom = rows - reserved - 1;
+
+        let (_, row) = cursor::position()?;
+        execute!(
+            self.stdout_handle,
+            crossterm::style::Print("\n".repeat(reserved as usize)),
+            SetScrollRegion(Some((0, region_bottom))),
+            cursor::MoveTo(0, row.min(region_bottom)),
+            SavePosition
+        )?;
+
+        self.input_area_top = region_bottom + 1;
+        self.fixed_layout_active = true;
+        Ok(())
+    }
+
+    /// Moves the boundary between the log region and the input area of the fixed
+    /// layout after the number of reserved rows changed.
+    ///
+    /// When the input area grows, the log region is scrolled up first so its newest
+    /// lines stay visible. The caller redraws the input area afterwards.
+    fn update_fixed_layout(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        let reserved = self.reserved_rows();
+        let rows = self.terminal_size.1;
+        if rows <= reserved {
+            return Ok(());
+        }
+        let new_top = rows - reserved;
+        let old_top = self.input_area_top;
+        if new_top == old_top {
+            return Ok(());
+        }
+
+        queue!(
+            self.stdout_handle,
+            cursor::Hide,
+            cursor::MoveTo(0, old_top),
+            Clear(ClearType::FromCursorDown)
+        )?;
+        if new_top < old_top {
+            let shift = old_top - new_top;
+            queue!(
+                self.stdout_handle,
+                cursor::MoveTo(0, old_top - 1),
+                crossterm::style::Print("\n".repeat(shift as usize)),
+                RestorePosition,
+                cursor::MoveUp(shift),
+                SavePosition
+            )?;
+        }
+        queue!(
+            self.stdout_handle,
+            SetScrollRegion(Some((0, new_top - 1))),
+            RestorePosition
+        )?;
+
+        self.input_area_top = new_top;
+        Ok(())
+    }
+
+    /// Returns the number of rows below the log region in the fixed layout.
+    ///
+    /// One per visible input line, one for completion hints and one for the status
+    /// bar if it has segments.
+    fn reserved_rows(&self) -> u16 {
+        let input_rows =
+            multiline::line_count(self.current_input.as_str()).min(self.max_input_rows());
+        input_rows as u16 + if self.status_bar.is_empty() { 1 } else { 2 }
+    }
+
+    /// Returns the maximum number of input lines shown at once.
+    fn max_input_rows(&self) -> usize {
+        usize::from(self.terminal_size.1 / 2).max(1)
+    }
+
     /// Prints the startup message to the terminal
     async fn print_startup_message(
         &mut self,
@@ -339,138 +1153,326 @@ impl TerminalApp {
         &mut self,
         event: Event,
     ) -> Result<bool, Box<dyn std::error::Error>> {
-        let mut should_quit = false;
+        let should_quit = false;
 
-        if let Event::Key(key_event) = &event {
-            if key_event.kind == KeyEventKind::Release {
-                return Ok(should_quit);
-            }
+        if let Event::Resize(cols, rows) = event {
+            self.handle_resize(cols, rows)?;
+            return Ok(should_quit);
+        }
+        if let Event::Paste(text) = event {
+            return self.handle_paste(&text).await;
+        }
 
-            if let Some(last_event) = &self.last_key_event
-                && last_event.code == key_event.code
-                && last_event.modifiers == key_event.modifiers
-                && last_event.kind == key_event.kind
-            {
-                let is_control_key = match key_event.code {
-                    KeyCode::Char('c') if key_event.modifiers == KeyModifiers::CONTROL => true,
-                    KeyCode::Char('d') if key_event.modifiers == KeyModifiers::CONTROL => true,
-                    _ => false,
-                };
+        let Event::Key(key_event) = event else {
+            return Ok(should_quit);
+        };
+        let key_event = &key_event;
+        if !self.key_debouncer.accept(key_event, Instant::now()) {
+            return Ok(should_quit);
+        }
+
+        if self.edit_mode == EditMode::Vi
+            && self.history_search.is_none()
+            && self.pending_keys.is_empty()
+            && self.handle_vi_key(key_event)?
+        {
+            return Ok(should_quit);
+        }
 
-                if !is_control_key {
+        // Resolve the key (or chord) through the keymap
+        self.pending_keys.push(KeyPress::from(*key_event));
+        let action = match self.keymap.lookup(&self.pending_keys) {
+            KeymapMatch::Action(action) => {
+                self.pending_keys.clear();
+                Some(action)
+            }
+            KeymapMatch::Prefix => return Ok(should_quit),
+            KeymapMatch::Unbound => {
+                let was_chord = self.pending_keys.len() > 1;
+                self.pending_keys.clear();
+                if was_chord {
+                    // Unknown chords are discarded, like readline does
                     return Ok(should_quit);
                 }
+                None
             }
+        };
 
-            match key_event.code {
-                KeyCode::Char('c') if key_event.modifiers == KeyModifiers::CONTROL => {
-                    self.last_key_event = Some(*key_event);
-                }
-                KeyCode::Char('d') if key_event.modifiers == KeyModifiers::CONTROL => {
-                    self.last_key_event = Some(*key_event);
+        if self.history_search.is_some() && self.handle_search_key(key_event, action)? {
+            return Ok(should_quit);
+        }
+
+        let last_edit = std::mem::take(&mut self.last_edit);
+        match action {
+            Some(action) => self.execute_action(action, last_edit).await,
+            None => {
+                if let KeyCode::Char(c) = key_event.code
+                    && is_text_modifiers(key_event.modifiers)
+                {
+                    self.with_undo(EditKind::Insert, |app| app.handle_char_input(c));
+                    self.update_completions();
+                    self.render_input_line()?;
                 }
-                _ => {}
+                Ok(should_quit)
             }
         }
+    }
 
-        if let Event::Key(KeyEvent {
-            code, modifiers, ..
-        }) = event
-        {
-            match code {
-                KeyCode::Char('d') if modifiers == KeyModifiers::CONTROL => {
-                    should_quit = self.handle_ctrl_d().await?;
+    /// Runs a single editor action and returns whether the app should quit.
+    ///
+    /// This is what key presses resolve to through `keymap`, but it can also be
+    /// called directly, e.g. to trigger actions from custom key handling.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if rendering fails.
+    pub async fn run_action(
+        &mut self,
+        action: EditorAction,
+    ) -> Result<bool, Box<dyn std::error::Error>> {
+        let last_edit = std::mem::take(&mut self.last_edit);
+        self.execute_action(action, last_edit).await
+    }
+
+    /// Runs an editor action with the given previous edit, see `run_action()`.
+    async fn execute_action(
+        &mut self,
+        action: EditorAction,
+        last_edit: LastEdit,
+    ) -> Result<bool, Box<dyn std::error::Error>> {
+        let completions_shown = !self.current_completions.is_empty() && !self.completions_hidden;
+
+        match action {
+            EditorAction::Submit
+                if self
+                    .continuation
+                    .as_ref()
+                    .is_some_and(|incomplete| incomplete(self.current_input.as_str())) =>
+            {
+                self.with_undo(EditKind::Other, |app| app.insert_newline());
+                self.update_completions();
+            }
+            EditorAction::InsertNewline => {
+                self.with_undo(EditKind::Other, |app| app.insert_newline());
+                self.update_completions();
+            }
+            EditorAction::Submit => {
+                // Every new line starts in insert mode
+                self.vi.enter_insert();
+                let prompt = self.prompt.render();
+                let (should_exit, input) = self.handle_enter_key(&prompt).await?;
+                self.submitted_inputs.extend(input);
+                return Ok(should_exit);
+            }
+            EditorAction::Interrupt => {
+                // On an empty line, a running job is cancelled rather than the console
+                if self.current_input.is_empty()
+                    && let Some(job) = self.jobs.cancel_newest()
+                {
+                    self.warn(&format!("[{}] Cancelled: {}", job.id, job.input));
+                    return Ok(false);
                 }
-                KeyCode::Char('c') if modifiers == KeyModifiers::CONTROL => {
-                    let (quit, message) = self.handle_ctrl_c().await?;
-                    should_quit = quit;
-                    self.print_log_entry(&message);
+                let (quit, message) = self.handle_ctrl_c().await?;
+                self.print_log_entry(&message);
+                return Ok(quit);
+            }
+            EditorAction::EndOfFile => {
+                if self.current_input.is_empty() {
+                    return self.handle_ctrl_d().await;
                 }
-                KeyCode::Esc => {
-                    // Toggle completions visibility
-                    self.completions_hidden = !self.completions_hidden;
-                    self.focus_on_completions = false; // Move focus back to text input
-                    self.render_input_line()?;
+                // Like readline, Ctrl+D only exits on an empty line
+                self.with_undo(EditKind::Delete, |app| app.delete_char_forward());
+                self.update_completions();
+            }
+            EditorAction::Abort => {}
+            EditorAction::Undo => self.undo_edit(),
+            EditorAction::Redo => self.redo_edit(),
+            EditorAction::ToggleCompletions => {
+                // Toggle completions visibility
+                self.completions_hidden = !self.completions_hidden;
+                self.focus_on_completions = false; // Move focus back to text input
+            }
+            // Applying a completion replaces the input wholesale, so it is one undo step
+            EditorAction::Complete => self.with_undo(EditKind::Other, |app| app.handle_tab_key()),
+            EditorAction::ReverseSearch => self.start_history_search(),
+            EditorAction::HistoryPrevious => {
+                if self.focus_on_completions && completions_shown {
+                    // Move focus back to text input
+                    self.focus_on_completions = false;
+                } else if !self.move_to_adjacent_line(false) {
+                    self.handle_up_key();
                 }
-                KeyCode::Up => {
-                    if self.focus_on_completions
-                        && !self.current_completions.is_empty()
-                        && !self.completions_hidden
-                    {
-                        // Move focus back to text input
-                        self.focus_on_completions = false;
-                        self.render_input_line()?;
-                    } else {
-                        self.handle_up_key();
-                        self.render_input_line()?;
-                    }
+            }
+            EditorAction::HistoryNext => {
+                if !self.focus_on_completions && completions_shown {
+                    // Move focus to completions
+                    self.focus_on_completions = true;
+                } else if !self.move_to_adjacent_line(true) {
+                    self.handle_down_key();
                 }
-                KeyCode::Down => {
-                    if !self.focus_on_completions
-                        && !self.current_completions.is_empty()
-                        && !self.completions_hidden
-                    {
-                        // Move focus to completions
-                        self.focus_on_completions = true;
-                        self.render_input_line()?;
+            }
+            EditorAction::MoveLeft => {
+                if self.focus_on_completions && !self.current_completions.is_empty() {
+                    if self.selected_completion_index == 0 {
+                        self.selected_completion_index = self.current_completions.len() - 1;
                     } else {
-                        self.handle_down_key();
-                        self.render_input_line()?;
+                        self.selected_completion_index -= 1;
                     }
+                } else if self.current_input.move_left() {
+                    self.undo_stack.break_group();
                 }
-                KeyCode::Left => {
-                    if self.focus_on_completions && !self.current_completions.is_empty() {
-                        if self.selected_completion_index == 0 {
-                            self.selected_completion_index = self.current_completions.len() - 1;
-                        } else {
-                            self.selected_completion_index -= 1;
-                        }
-                        self.render_input_line()?;
-                    } else if self.cursor_position > 0 {
-                        self.cursor_position -= 1;
-                        self.render_input_line()?;
-                    }
-                }
-                KeyCode::Right => {
-                    if self.focus_on_completions && !self.current_completions.is_empty() {
-                        if self.selected_completion_index == self.current_completions.len() - 1 {
-                            self.selected_completion_index = 0;
-                        } else {
-                            self.selected_completion_index += 1;
-                        }
-                        self.render_input_line()?;
-                    } else if self.cursor_position < self.current_input.chars().count() {
-                        self.cursor_position += 1;
-                        self.render_input_line()?;
-                    }
-                }
-                KeyCode::Tab => {
-                    self.handle_tab_key();
-                    self.render_input_line()?;
-                }
-                KeyCode::Enter => {
-                    let (should_exit, _) = self.handle_enter_key("> ").await?;
-                    if should_exit {
-                        return Ok(true);
+            }
+            EditorAction::MoveRight => {
+                if self.focus_on_completions && !self.current_completions.is_empty() {
+                    if self.selected_completion_index == self.current_completions.len() - 1 {
+                        self.selected_completion_index = 0;
+                    } else {
+                        self.selected_completion_index += 1;
                     }
-                }
-                KeyCode::Char(c) => {
-                    self.handle_char_input(c);
+                } else if self.current_input.move_right() {
+                    self.undo_stack.break_group();
+                } else {
+                    self.with_undo(EditKind::Other, |app| {
+                        app.accept_suggestion(true);
+                    });
                     self.update_completions();
-                    self.render_input_line()?;
                 }
-                KeyCode::Backspace => {
-                    if self.cursor_position > 0 {
-                        self.remove_char_at(self.cursor_position - 1);
-                        self.cursor_position -= 1;
-                        self.update_completions();
-                        self.render_input_line()?;
-                    }
+            }
+            _ => {
+                let kind = match action {
+                    EditorAction::DeleteBackward | EditorAction::DeleteForward => EditKind::Delete,
+                    _ => EditKind::Other,
+                };
+                self.with_undo(kind, |app| app.apply_edit_action(action, last_edit));
+                self.update_completions();
+            }
+        }
+
+        // Keys that fall through to the keymap, like End or Up, can leave the cursor
+        // past the end
+        self.clamp_vi_cursor();
+        self.render_input_line()?;
+        Ok(false)
+    }
+
+    /// Inserts pasted text at the cursor.
+    ///
+    /// In `PasteMode::Buffer`, the text is inserted as a whole, newlines included, as
+    /// one undo step. In `PasteMode::Submit`, every complete line is submitted like
+    /// typed input and queued for `read_input()`; the rest stays in the input.
+    ///
+    /// Returns whether a submitted line requested exit, like a typed Enter.
+    async fn handle_paste(&mut self, text: &str) -> Result<bool, Box<dyn std::error::Error>> {
+        // Pasting ends a reverse search, keeping the match
+        self.finish_history_search(true);
+        let text = paste::normalize_paste(text);
+
+        if self.paste_mode == PasteMode::Submit && text.contains('\n') {
+            if self.submit_pasted_lines(&text).await? {
+                return Ok(true);
+            }
+        } else {
+            self.with_undo(EditKind::Other, |app| {
+                app.insert_at_cursor(&text);
+                app.history_index = None;
+            });
+        }
+
+        self.update_completions();
+        self.render_input_line()?;
+        Ok(false)
+    }
+
+    /// Submits pasted lines one by one, as if Enter was pressed after each.
+    ///
+    /// Lines the continuation predicate considers incomplete are kept together, like
+    /// typed multi-line input. Text after the cursor ends up after the last line.
+    /// Stops at the first line that requests exit and returns `true`, dropping the
+    /// lines after it.
+    async fn submit_pasted_lines(
+        &mut self,
+        text: &str,
+    ) -> Result<bool, Box<dyn std::error::Error>> {
+        let after_cursor = self
+            .current_input
+            .drain(self.current_input.cursor()..self.current_input.len());
+        let prompt = self.prompt.render();
+
+        let mut lines = text.split('\n');
+        self.current_input
+            .insert_str(lines.next().unwrap_or_default());
+        for line in lines {
+            let incomplete = self
+                .continuation
+                .as_ref()
+                .is_some_and(|incomplete| incomplete(self.current_input.as_str()));
+            if incomplete {
+                self.current_input.insert_char('\n');
+            } else {
+                let (should_exit, input) = self.handle_enter_key(&prompt).await?;
+                self.submitted_inputs.extend(input);
+                self.current_input.clear();
+                if should_exit {
+                    return Ok(true);
                 }
-                _ => {}
             }
+            self.current_input.insert_str(line);
         }
-        Ok(should_quit)
+
+        let cursor = self.current_input.cursor();
+        self.current_input.insert_str(&after_cursor);
+        self.current_input.set_cursor(cursor);
+        self.history_index = None;
+        self.undo_stack.clear();
+        Ok(false)
+    }
+
+    /// Re-lays out the input area after the terminal was resized.
+    ///
+    /// In the fixed layout, the scroll region is moved to the new bottom rows. When
+    /// the terminal got shorter, logging continues at the bottom of the log region.
+    /// A terminal too small to hold the input area keeps the previous region until
+    /// it grows again.
+    fn handle_resize(&mut self, cols: u16, rows: u16) -> Result<(), Box<dyn std::error::Error>> {
+        let old_rows = self.terminal_size.1;
+        self.terminal_size = (cols, rows);
+
+        let reserved = self.reserved_rows();
+        if self.fixed_layout_active && rows > reserved {
+            let old_top = self.input_area_top;
+            let new_top = rows - reserved;
+            queue!(
+                self.stdout_handle,
+                cursor::Hide,
+                cursor::MoveTo(0, old_top.min(new_top)),
+                Clear(ClearType::FromCursorDown),
+                SetScrollRegion(Some((0, new_top - 1)))
+            )?;
+            if rows < old_rows {
+                // The saved log cursor may now be inside the input area
+                queue!(
+                    self.stdout_handle,
+                    cursor::MoveTo(0, new_top - 1),
+                    crossterm::style::Print("\n"),
+                    SavePosition
+                )?;
+            }
+            self.input_area_top = new_top;
+        }
+
+        if self.input_active {
+            self.render_input_line()?;
+        } else {
+            self.stdout_handle.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Returns the terminal size as (columns, rows).
+    ///
+    /// The size is read when the app is created and kept up to date by resize events.
+    pub fn terminal_size(&self) -> (u16, u16) {
+        self.terminal_size
     }
 
     /// Shuts down the terminal and displays exit messages.
@@ -486,6 +1488,13 @@ impl TerminalApp {
         &mut self,
         exit_message: &str,
     ) -> Result<(), Box<dyn std::error::Error>> {
+        self.input_active = false;
+        let _ = execute!(self.stdout_handle, DisableBracketedPaste);
+        if self.fixed_layout_active {
+            self.clear_input_line();
+            execute!(self.stdout_handle, SetScrollRegion(None), RestorePosition)?;
+            self.fixed_layout_active = false;
+        }
         if self.raw_mode_enabled {
             disable_raw_mode()?;
             execute!(self.stdout_handle, DisableMouseCapture, cursor::Show)?;
@@ -502,11 +1511,15 @@ impl TerminalApp {
     ///
     /// This method processes terminal events in a non-blocking manner and returns
     /// when the user presses Enter with non-empty input or when a quit signal is received.
+    /// Messages sent through a `ConsoleHandle` are printed while waiting, and
+    /// `ConsoleHandle::request_shutdown()` makes it return `Ok(None)`. Input starting
+    /// with an alias is expanded first, and the commands of a macro are returned by
+    /// consecutive calls, see `define_alias()`.
     ///
     /// # Returns
     ///
     /// - `Ok(Some(String))` - User entered a non-empty string
-    /// - `Ok(None)` - User should exit (Ctrl+C, Ctrl+D, or should_exit flag set)
+    /// - `Ok(None)` - User should exit (Ctrl+C, Ctrl+D, should_exit flag set, or shutdown requested)
     ///
     /// # Errors
     ///
@@ -531,29 +1544,48 @@ impl TerminalApp {
     /// }
     /// ```
     pub async fn read_input(&mut self) -> Result<Option<String>, Box<dyn std::error::Error>> {
-        loop {
-            tokio::select! {
-                _ = tokio::time::sleep(tokio::time::Duration::from_millis(50)) => {
-                    if poll(std::time::Duration::from_millis(0))?
-                        && let Ok(event) = event::read() {
-                            if let Event::Key(KeyEvent { code: KeyCode::Enter, .. }) = event {
-                                let (should_exit, input) = self.handle_enter_key("> ").await?;
-                                if should_exit {
-                                    return Ok(None);
-                                }
-                                if let Some(user_input) = input {
-                                    return Ok(Some(user_input));
-                                }
-                            } else if self.process_event(event).await? {
-                                return Ok(None);
-                            }
-                        }
-                }
-            }
+        self.drain_messages();
+        let mut events = self.event_stream.take().unwrap_or_default();
+        let result = self.read_input_from(&mut events).await;
+        self.event_stream = Some(events);
+        result
+    }
 
+    /// Event loop behind `read_input()`.
+    ///
+    /// Awaits terminal events and `ConsoleHandle` messages together, so an idle
+    /// console does not wake up until something actually happens.
+    async fn read_input_from(
+        &mut self,
+        events: &mut EventStream,
+    ) -> Result<Option<String>, Box<dyn std::error::Error>> {
+        loop {
             if self.should_exit {
                 return Ok(None);
             }
+            if let Some(command) = self.expanded_inputs.pop_front() {
+                return Ok(Some(command));
+            }
+            if let Some(user_input) = self.submitted_inputs.pop_front() {
+                let commands = self.aliases.expand(&user_input);
+                self.expanded_inputs.extend(commands);
+                continue;
+            }
+
+            tokio::select! {
+                Some(message) = self.message_receiver.recv() => {
+                    self.handle_console_message(message);
+                }
+                maybe_event = events.next() => {
+                    let Some(event) = maybe_event else {
+                        // The terminal input stream has closed, nothing more can be read
+                        return Ok(None);
+                    };
+                    if self.process_event(event?).await? {
+                        return Ok(None);
+                    }
+                }
+            }
         }
     }
 
@@ -576,70 +1608,341 @@ impl TerminalApp {
     ) -> Result<(), Box<dyn std::error::Error>> {
         self.init_terminal(startup_message).await?;
 
-        while let Some(input) = self.read_input().await? {
-            self.info(&format!("You entered: {}", input));
+        while let Some(input) = self.read_input().await? {
+            self.info(&format!("You entered: {}", input));
+        }
+
+        self.shutdown_terminal(exit_message).await?;
+        Ok(())
+    }
+
+    /// Clears the input area: the input line, completion hints and status bar.
+    ///
+    /// Everything from the top row of the input area down is cleared, so the cursor
+    /// ends up at the start of that row.
+    pub fn clear_input_line(&mut self) {
+        self.queue_clear_input_line();
+        let _ = self.stdout_handle.flush();
+    }
+
+    /// Queues the commands clearing the input area, without flushing.
+    ///
+    /// Used while drawing a frame, which is flushed once at its end.
+    fn queue_clear_input_line(&mut self) {
+        if self.fixed_layout_active {
+            let _ = queue!(
+                self.stdout_handle,
+                cursor::MoveTo(0, self.input_area_top),
+                Clear(ClearType::FromCursorDown)
+            );
+            return;
+        }
+        // Rows drawn at an older, wider size may have been reflowed onto several rows
+        let cols = usize::from(self.terminal_size.0.max(1));
+        let rows_up = self.input_cursor_column as usize / cols
+            + self
+                .rendered_rows_above
+                .drain(..)
+                .map(|width| width.max(1).div_ceil(cols))
+                .sum::<usize>();
+        if rows_up > 0 {
+            let _ = queue!(self.stdout_handle, cursor::MoveUp(rows_up as u16));
+        }
+        self.input_cursor_column = 0;
+        let _ = queue!(
+            self.stdout_handle,
+            cursor::MoveToColumn(0),
+            Clear(ClearType::FromCursorDown)
+        );
+    }
+
+    /// Prints a log entry while preserving the input line.
+    ///
+    /// Clears the input line, outputs the log message, then re-renders the input line
+    /// on a new line without clearing first. In the fixed layout, the entry is written
+    /// into the log region instead and the input area is left in place.
+    pub fn print_log_entry(&mut self, log_line: &str) {
+        if self.fixed_layout_active {
+            self.print_log_entry_fixed(log_line);
+            return;
+        }
+        self.queue_clear_input_line();
+        if log_line.contains('\n') {
+            for line in log_line.lines() {
+                let _ = writeln!(self.stdout_handle, "{}", line);
+                let _ = queue!(self.stdout_handle, cursor::MoveToColumn(0));
+            }
+        } else {
+            let _ = writeln!(self.stdout_handle, "{}", log_line);
+        }
+
+        // let _ = self.stdout_handle.flush();
+        let _ = queue!(self.stdout_handle, cursor::MoveToColumn(0));
+        let _ = self.render_input_line_no_clear();
+    }
+
+    /// Writes a log entry at the log cursor of the fixed layout.
+    ///
+    /// Lines are written at the saved log cursor; once the log region is full, the
+    /// terminal scrolls it without touching the input area below. The input line is
+    /// not redrawn, the cursor just returns to where it was.
+    fn print_log_entry_fixed(&mut self, log_line: &str) {
+        let lines: Vec<&str> = if log_line.contains('\n') {
+            log_line.lines().collect()
+        } else {
+            vec![log_line]
+        };
+
+        let _ = queue!(self.stdout_handle, cursor::Hide, RestorePosition);
+        for line in lines {
+            let _ = queue!(
+                self.stdout_handle,
+                Clear(ClearType::CurrentLine),
+                crossterm::style::Print(line),
+                crossterm::style::Print("\r\n")
+            );
+        }
+        let _ = queue!(
+            self.stdout_handle,
+            SavePosition,
+            cursor::MoveTo(self.input_cursor_column, self.input_cursor_row),
+            cursor::Show
+        );
+        let _ = self.stdout_handle.flush();
+    }
+
+    /// Replaces the prompt shown in front of the input.
+    ///
+    /// Accepts a styled `Prompt` or plain text, and can be called at any time; the
+    /// input line is redrawn right away. Use `ConsoleHandle::set_prompt()` to change
+    /// the prompt from another task.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crossterm::style::Color;
+    /// use daemon_console_lite::{Prompt, TerminalApp};
+    ///
+    /// let mut app = TerminalApp::new();
+    /// app.set_prompt(
+    ///     Prompt::new()
+    ///         .colored(app.app_name.clone(), Color::Cyan)
+    ///         .colored(" [maintenance]", Color::Yellow)
+    ///         .text(" > "),
+    /// );
+    /// assert_eq!(app.prompt().plain_text(), "Daemon Console [maintenance] > ");
+    ///
+    /// app.set_prompt("$ ");
+    /// assert_eq!(app.prompt().width(), 2);
+    /// ```
+    pub fn set_prompt(&mut self, prompt: impl Into<Prompt>) {
+        self.prompt = prompt.into();
+        if self.input_active {
+            let _ = self.render_input_line();
+        }
+    }
+
+    /// Returns the current prompt.
+    pub fn prompt(&self) -> &Prompt {
+        &self.prompt
+    }
+
+    /// Enables multi-line input with the default continuation predicate.
+    ///
+    /// Enter on an input with an unclosed bracket or a trailing backslash then starts
+    /// a new line instead of submitting, see `multiline::is_incomplete()`. Up and Down
+    /// move between lines before navigating history. Alt+Enter always starts a new
+    /// line, whether multi-line input is enabled or not.
+    pub fn enable_multiline(&mut self) {
+        self.continuation = Some(Box::new(multiline::is_incomplete));
+    }
+
+    /// Enables multi-line input with a custom continuation predicate.
+    ///
+    /// The predicate gets the whole input and returns true if it is incomplete.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daemon_console_lite::TerminalApp;
+    ///
+    /// let mut app = TerminalApp::new();
+    /// // Scripts end with a line containing only "end"
+    /// app.set_continuation_predicate(|input| {
+    ///     input.starts_with("script") && input.lines().last() != Some("end")
+    /// });
+    /// assert!(app.is_multiline_enabled());
+    /// ```
+    pub fn set_continuation_predicate(
+        &mut self,
+        predicate: impl Fn(&str) -> bool + Send + Sync + 'static,
+    ) {
+        self.continuation = Some(Box::new(predicate));
+    }
+
+    /// Disables multi-line input, so Enter always submits.
+    pub fn disable_multiline(&mut self) {
+        self.continuation = None;
+    }
+
+    /// Checks if multi-line input is enabled.
+    pub fn is_multiline_enabled(&self) -> bool {
+        self.continuation.is_some()
+    }
+
+    /// Enables syntax highlighting of the input with `DefaultHighlighter`.
+    ///
+    /// Commands known to the completion tree are shown in green and an unknown
+    /// command in red; numbers, quoted strings and flags get their own colors.
+    pub fn enable_highlighting(&mut self) {
+        self.set_highlighter(DefaultHighlighter);
+    }
+
+    /// Enables syntax highlighting of the input with a custom highlighter.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crossterm::style::Color;
+    /// use daemon_console_lite::TerminalApp;
+    /// use daemon_console_lite::highlight::{HighlightSpan, Highlighter};
+    /// use daemon_console_lite::tab::TabTree;
+    ///
+    /// /// Shows every input in bold.
+    /// struct Bold;
+    ///
+    /// impl Highlighter for Bold {
+    ///     fn highlight(&self, input: &str, _tab_tree: Option<&TabTree>) -> Vec<HighlightSpan> {
+    ///         vec![HighlightSpan { range: 0..input.len(), color: None, bold: true }]
+    ///     }
+    /// }
+    ///
+    /// let mut app = TerminalApp::new();
+    /// app.set_highlighter(Bold);
+    /// assert!(app.is_highlighting_enabled());
+    /// ```
+    pub fn set_highlighter(&mut self, highlighter: impl Highlighter + 'static) {
+        self.highlighter = Some(Box::new(highlighter));
+        if self.input_active {
+            let _ = self.render_input_line();
+        }
+    }
+
+    /// Disables syntax highlighting, drawing the input as plain text.
+    pub fn disable_highlighting(&mut self) {
+        self.highlighter = None;
+        if self.input_active {
+            let _ = self.render_input_line();
+        }
+    }
+
+    /// Checks if syntax highlighting is enabled.
+    pub fn is_highlighting_enabled(&self) -> bool {
+        self.highlighter.is_some()
+    }
+
+    /// Replaces the prompt shown in front of continuation lines, `"... "` by default.
+    pub fn set_continuation_prompt(&mut self, prompt: impl Into<Prompt>) {
+        self.continuation_prompt = prompt.into();
+        if self.input_active {
+            let _ = self.render_input_line();
+        }
+    }
+
+    /// Returns the styled prompt text printed in front of the input.
+    ///
+    /// In vi mode, the prompt starts with the current mode indicator (`[I]` or `[N]`).
+    fn rendered_prompt(&self) -> String {
+        match self.edit_mode {
+            EditMode::Vi => format!("{}{}", self.vi.mode.indicator(), self.prompt.render()),
+            EditMode::Emacs => self.prompt.render(),
         }
+    }
 
-        self.shutdown_terminal(exit_message).await?;
-        Ok(())
+    /// Returns the display width of `rendered_prompt()`.
+    fn prompt_width(&self) -> usize {
+        match self.edit_mode {
+            EditMode::Vi => self.vi.mode.indicator().width() + self.prompt.width(),
+            EditMode::Emacs => self.prompt.width(),
+        }
     }
 
-    /// Clears the current input line and completion hints if rendered.
+    /// Sets a status bar segment, adding it at the end if it doesn't exist yet.
     ///
-    /// If `hints_rendered` is true, this clears both the input line and the line below it
-    /// containing completion hints. Otherwise, only the current line is cleared.
-    pub fn clear_input_line(&mut self) {
-        if self.hints_rendered {
-            let _ = execute!(
-                self.stdout_handle,
-                cursor::MoveToColumn(0),
-                Clear(ClearType::CurrentLine),
-                cursor::MoveDown(1),
-                Clear(ClearType::CurrentLine),
-                cursor::MoveUp(1),
-                cursor::MoveToColumn(0)
-            );
-            self.hints_rendered = false;
-        } else {
-            let _ = execute!(
-                self.stdout_handle,
-                cursor::MoveToColumn(0),
-                Clear(ClearType::CurrentLine)
-            );
-        }
+    /// The status bar is only drawn while it has segments. Use
+    /// `ConsoleHandle::set_status()` to update segments from another task.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daemon_console_lite::TerminalApp;
+    ///
+    /// let mut app = TerminalApp::new();
+    /// app.set_status("uptime", "up 0s");
+    /// app.set_status("queue", "queue: 0");
+    /// app.set_status("uptime", "up 5s");
+    /// assert_eq!(app.status_bar().plain_text(), "up 5s | queue: 0");
+    /// ```
+    pub fn set_status(&mut self, name: &str, text: impl Into<String>) {
+        self.update_status(|bar| bar.set(name, text, None));
     }
 
-    /// Prints a log entry while preserving the input line.
+    /// Sets a status bar segment shown in the given color.
+    pub fn set_status_colored(&mut self, name: &str, text: impl Into<String>, color: Color) {
+        self.update_status(|bar| bar.set(name, text, Some(color)));
+    }
+
+    /// Removes a status bar segment.
+    pub fn remove_status(&mut self, name: &str) {
+        self.update_status(|bar| {
+            bar.remove(name);
+        });
+    }
+
+    /// Returns the status bar.
+    pub fn status_bar(&self) -> &StatusBar {
+        &self.status_bar
+    }
+
+    /// Applies a change to the status bar and redraws the input area.
     ///
-    /// Clears the input line, outputs the log message, then re-renders the input line
-    /// on a new line without clearing first.
-    pub fn print_log_entry(&mut self, log_line: &str) {
-        self.clear_input_line();
-        if log_line.contains('\n') {
-            for line in log_line.lines() {
-                let _ = writeln!(self.stdout_handle, "{}", line);
-                let _ = execute!(self.stdout_handle, cursor::MoveToColumn(0));
-            }
-        } else {
-            let _ = writeln!(self.stdout_handle, "{}", log_line);
+    /// In the fixed layout, the status bar row is reserved or given back to the log
+    /// region when the bar becomes visible or empty.
+    fn update_status(&mut self, edit: impl FnOnce(&mut StatusBar)) {
+        let was_empty = self.status_bar.is_empty();
+        edit(&mut self.status_bar);
+        if !self.input_active {
+            return;
         }
+        if self.fixed_layout_active && was_empty != self.status_bar.is_empty() {
+            let _ = self.update_fixed_layout();
+        }
+        let _ = self.render_input_line();
+    }
 
-        // let _ = self.stdout_handle.flush();
-        let _ = execute!(self.stdout_handle, cursor::MoveToColumn(0));
-        let _ = self.render_input_line_no_clear();
+    /// Returns whether the status bar is drawn above the input line.
+    fn status_above(&self) -> bool {
+        !self.status_bar.is_empty() && self.status_position == StatusPosition::Above
+    }
+
+    /// Returns the row of the first input line in the fixed layout.
+    fn input_row(&self) -> u16 {
+        self.input_area_top + u16::from(self.status_above())
+    }
+
+    /// Returns the current vi sub-mode, or `None` when not in vi mode.
+    pub fn vi_mode(&self) -> Option<ViMode> {
+        match self.edit_mode {
+            EditMode::Vi => Some(self.vi.mode),
+            EditMode::Emacs => None,
+        }
     }
 
-    /// Calculates the visual cursor position accounting for Unicode character widths.
+    /// Returns the number of columns available to the input text after the prompt.
     ///
-    /// Returns the column position where the cursor should be displayed,
-    /// including the 2-character prompt ("> ").
-    fn calculate_visual_cursor_pos(&self) -> usize {
-        2 + self
-            .current_input
-            .chars()
-            .take(self.cursor_position)
-            .map(|c| c.width().unwrap_or(0))
-            .sum::<usize>()
+    /// The last column is left free so the line never wraps.
+    fn input_width(&self, prompt_width: usize) -> usize {
+        (self.terminal_size.0 as usize).saturating_sub(prompt_width + 1)
     }
 
     /// Truncates a string to the specified maximum length, adding "..." if truncated.
@@ -665,23 +1968,59 @@ impl TerminalApp {
         format!("{}...", truncated_chars.iter().collect::<String>())
     }
 
-    /// Renders prompt, input text, and completion hints.
+    /// Renders the input area: status bar, prompt, input text and completion hints.
     ///
     /// This is the core rendering logic shared by both `render_input_line()`
-    /// and `render_input_line_no_clear()`.
+    /// and `render_input_line_no_clear()`. Rows below the cursor are reached
+    /// with line breaks and left again with relative moves, so this also works
+    /// when drawing them scrolls the screen.
     fn render_input_content(&mut self) -> Result<(), Box<dyn std::error::Error>> {
-        execute!(
-            self.stdout_handle,
-            crossterm::style::Print("> "),
-            crossterm::style::Print(&self.current_input)
-        )?;
+        if self.fixed_layout_active {
+            queue!(self.stdout_handle, cursor::MoveTo(0, self.input_area_top))?;
+        }
+        if self.status_above() {
+            let width = self.render_status_bar()?;
+            queue!(self.stdout_handle, crossterm::style::Print("\r\n"))?;
+            if !self.fixed_layout_active {
+                self.rendered_rows_above.push(width);
+            }
+        }
+
+        self.input_cursor_row = self.input_row();
+        let (rows_after_cursor, visual_cursor_pos) = if self.history_search.is_some() {
+            (0, self.render_search_content()?)
+        } else {
+            self.render_input_lines()?
+        };
 
-        if !self.current_completions.is_empty() {
-            self.render_completion_hints()?;
+        let show_hints = self.history_search.is_none()
+            && !self.current_completions.is_empty()
+            && !self.completions_hidden;
+        let status_below =
+            !self.status_bar.is_empty() && self.status_position == StatusPosition::Below;
+        let mut rows_below = 0;
+        // The fixed layout keeps the hints row even when empty, so the status bar doesn't jump
+        if show_hints || (status_below && self.fixed_layout_active) {
+            queue!(self.stdout_handle, crossterm::style::Print("\r\n"))?;
+            rows_below += 1;
+            if show_hints {
+                self.render_completion_hints()?;
+            }
+        }
+        if status_below {
+            queue!(self.stdout_handle, crossterm::style::Print("\r\n"))?;
+            rows_below += 1;
+            self.render_status_bar()?;
+        }
+        if rows_below + rows_after_cursor > 0 {
+            queue!(
+                self.stdout_handle,
+                cursor::MoveUp(rows_below + rows_after_cursor)
+            )?;
         }
 
-        let visual_cursor_pos = self.calculate_visual_cursor_pos();
-        execute!(
+        self.input_cursor_column = visual_cursor_pos as u16;
+        queue!(
             self.stdout_handle,
             cursor::MoveToColumn(visual_cursor_pos as u16),
             cursor::Show
@@ -690,15 +2029,163 @@ impl TerminalApp {
         Ok(())
     }
 
+    /// Renders the prompt and the visible lines of the input, one row per line.
+    ///
+    /// Lines after the first get the continuation prompt. The cursor line scrolls
+    /// horizontally around the cursor, other lines show their start. Leaves the
+    /// cursor on the last drawn row and returns the number of rows drawn after the
+    /// cursor row, and the cursor column.
+    fn render_input_lines(&mut self) -> Result<(u16, usize), Box<dyn std::error::Error>> {
+        let (cursor_line, cursor_col) = self.current_input.line_col();
+        let line_count = multiline::line_count(self.current_input.as_str());
+        let (first, end) = self.visible_input_lines(line_count, cursor_line);
+        self.input_cursor_row += (cursor_line - first) as u16;
+        let suggestion = self.current_suggestion();
+        let input = self.current_input.as_str();
+        let spans = self
+            .highlighter
+            .as_ref()
+            .map(|highlighter| highlighter.highlight(input, self.tab_tree.as_ref()))
+            .unwrap_or_default();
+
+        let mut cursor_column = 0;
+        let mut line_start = 0;
+        for (idx, line) in input.split('\n').enumerate() {
+            let text_start = line_start;
+            line_start += line.len() + 1;
+            if idx < first || idx >= end {
+                continue;
+            }
+            if idx > first {
+                queue!(self.stdout_handle, crossterm::style::Print("\r\n"))?;
+            }
+            let (prompt, prompt_width) = if idx == 0 {
+                (self.rendered_prompt(), self.prompt_width())
+            } else {
+                (
+                    self.continuation_prompt.render(),
+                    self.continuation_prompt.width(),
+                )
+            };
+            let width = self.input_width(prompt_width);
+            let text = if idx == cursor_line {
+                let (mut text, column) = scrolled_line(
+                    line,
+                    cursor_col,
+                    &mut self.input_scroll_offset,
+                    width,
+                    &spans,
+                    text_start,
+                );
+                cursor_column = prompt_width + column;
+                if let Some(suggestion) = &suggestion {
+                    // Multi-line entries are suggested on one row
+                    let shown = suggestion.replace('\n', "↵");
+                    let fitted = truncate_to_width(&shown, width.saturating_sub(column));
+                    text.push_str(&format!(
+                        "{}{}{}",
+                        SetForegroundColor(Color::DarkGrey),
+                        fitted,
+                        ResetColor
+                    ));
+                }
+                text
+            } else {
+                if idx < cursor_line && !self.fixed_layout_active {
+                    self.rendered_rows_above
+                        .push(prompt_width + line.width().min(width));
+                }
+                scrolled_line(line, 0, &mut 0, width, &spans, text_start).0
+            };
+            queue!(
+                self.stdout_handle,
+                crossterm::style::Print(prompt),
+                crossterm::style::Print(text)
+            )?;
+        }
+        Ok(((end - 1 - cursor_line) as u16, cursor_column))
+    }
+
+    /// Returns the range of input lines to draw, keeping the cursor line visible.
+    fn visible_input_lines(&mut self, line_count: usize, cursor_line: usize) -> (usize, usize) {
+        let max = self.max_input_rows();
+        if line_count <= max {
+            self.input_line_offset = 0;
+            return (0, line_count);
+        }
+        let mut first = self.input_line_offset.min(cursor_line);
+        if cursor_line >= first + max {
+            first = cursor_line + 1 - max;
+        }
+        first = first.min(line_count - max);
+        self.input_line_offset = first;
+        (first, first + max)
+    }
+
+    /// Renders the reverse-i-search prompt and the matched history entry.
+    ///
+    /// Completion hints are not shown while searching. Returns the column of the
+    /// start of the matched term inside the entry, where the cursor goes.
+    fn render_search_content(&mut self) -> Result<usize, Box<dyn std::error::Error>> {
+        let Some(search) = &self.history_search else {
+            return Ok(0);
+        };
+        let prompt = search.prompt();
+        let width = self.input_width(prompt.width());
+        let matched = search.matched(&self.command_history).unwrap_or("");
+        let term_offset = if search.term.is_empty() {
+            0
+        } else {
+            matched.find(&search.term).unwrap_or(0)
+        };
+        // Multi-line entries are shown on one row
+        let shown = matched.replace('\n', "↵");
+        let (line, cursor_column) = scrolled_line(
+            &shown,
+            matched[..term_offset].chars().count(),
+            &mut self.input_scroll_offset,
+            width,
+            &[],
+            0,
+        );
+
+        queue!(
+            self.stdout_handle,
+            crossterm::style::Print(&prompt),
+            crossterm::style::Print(line)
+        )?;
+        Ok(prompt.width() + cursor_column)
+    }
+
+    /// Draws the status bar on the current row, cut to the terminal width.
+    ///
+    /// Returns the display width of the drawn bar.
+    fn render_status_bar(&mut self) -> Result<usize, Box<dyn std::error::Error>> {
+        // Stay off the last column so the terminal doesn't wrap
+        let width = (self.terminal_size.0 as usize).saturating_sub(1);
+        let bar = self.status_bar.render(width);
+        queue!(
+            self.stdout_handle,
+            cursor::MoveToColumn(0),
+            crossterm::style::Print(bar),
+            Clear(ClearType::UntilNewLine)
+        )?;
+        Ok(self.status_bar.plain_text().width().min(width))
+    }
+
     /// Renders the input line with prompt, text, and completion hints.
     ///
     /// Clears the current line first, then displays the prompt and input text.
     /// If completions are available, renders hints below the input line.
-    /// Finally, positions the cursor at `cursor_position`.
+    /// Finally, positions the cursor at the input cursor.
     fn render_input_line(&mut self) -> Result<(), Box<dyn std::error::Error>> {
         let result = (|| -> Result<(), Box<dyn std::error::Error>> {
-            execute!(self.stdout_handle, cursor::Hide)?;
-            self.clear_input_line();
+            queue!(self.stdout_handle, cursor::Hide)?;
+            if self.fixed_layout_active {
+                // Multi-line input may need more or fewer rows than before
+                self.update_fixed_layout()?;
+            }
+            self.queue_clear_input_line();
             self.render_input_content()?;
             Ok(())
         })();
@@ -715,8 +2202,8 @@ impl TerminalApp {
     /// and completion hints if available.
     fn render_input_line_no_clear(&mut self) -> Result<(), Box<dyn std::error::Error>> {
         let result = (|| -> Result<(), Box<dyn std::error::Error>> {
-            execute!(self.stdout_handle, cursor::Hide)?;
-            execute!(self.stdout_handle, cursor::MoveToColumn(0))?;
+            queue!(self.stdout_handle, cursor::Hide)?;
+            queue!(self.stdout_handle, cursor::MoveToColumn(0))?;
             self.render_input_content()?;
             Ok(())
         })();
@@ -726,22 +2213,12 @@ impl TerminalApp {
         result
     }
 
-    /// Renders completion hints below the input line.
-    ///
-    /// Creates a new line for hints using a newline character, then uses
-    /// `SavePosition`/`RestorePosition` to render hints without permanently
-    /// affecting the cursor position. Sets `hints_rendered` to true.
+    /// Renders completion hints on the current row, the one below the input line.
     ///
     /// Displays up to tab_option_max_count completion candidates with smooth scrolling. The selected
     /// candidate is always visible and highlighted in cyan, others in dark gray.
     /// Each candidate is truncated to tab_option_max_length characters if needed.
     fn render_completion_hints(&mut self) -> Result<(), Box<dyn std::error::Error>> {
-        // Don't render if completions are hidden
-        if self.completions_hidden {
-            self.hints_rendered = false;
-            return Ok(());
-        }
-
         let total_count = self.current_completions.len();
         let max_display = self.tab_option_max_count;
 
@@ -761,22 +2238,20 @@ impl TerminalApp {
             (start, start + max_display)
         };
 
-        execute!(
-            self.stdout_handle,
-            SavePosition,
-            crossterm::style::Print("\n"),
-            cursor::MoveToColumn(0)
-        )?;
+        queue!(self.stdout_handle, cursor::MoveToColumn(0))?;
+        // Cut the hints at the terminal width so they never wrap onto another row
+        let mut remaining = (self.terminal_size.0 as usize).saturating_sub(1);
 
         let hidden_left = start_idx;
         let hidden_right = total_count - end_idx;
 
         // Show left hidden count if any
         if hidden_left > 0 {
-            execute!(
-                self.stdout_handle,
-                SetForegroundColor(Color::DarkGrey),
-                crossterm::style::Print(&format!(" (+{})", hidden_left))
+            queue!(self.stdout_handle, SetForegroundColor(Color::DarkGrey))?;
+            print_fitted(
+                &mut self.stdout_handle,
+                &format!(" (+{})", hidden_left),
+                &mut remaining,
             )?;
         }
 
@@ -789,7 +2264,7 @@ impl TerminalApp {
             .take(end_idx - start_idx)
         {
             if idx > start_idx || hidden_left > 0 {
-                execute!(self.stdout_handle, crossterm::style::Print(" "))?;
+                print_fitted(&mut self.stdout_handle, " ", &mut remaining)?;
             }
 
             let is_selected = idx == self.selected_completion_index;
@@ -799,7 +2274,7 @@ impl TerminalApp {
                 Color::DarkGrey
             };
 
-            execute!(self.stdout_handle, SetForegroundColor(color))?;
+            queue!(self.stdout_handle, SetForegroundColor(color))?;
 
             let mut item_text = String::from("[");
 
@@ -837,26 +2312,24 @@ impl TerminalApp {
             //     item_text.push('*');
             // }
 
-            execute!(self.stdout_handle, crossterm::style::Print(&item_text))?;
+            print_fitted(&mut self.stdout_handle, &item_text, &mut remaining)?;
         }
 
         // Show right hidden count if any
         if hidden_right > 0 {
-            execute!(
-                self.stdout_handle,
-                SetForegroundColor(Color::DarkGrey),
-                crossterm::style::Print(&format!(" (+{})", hidden_right))
+            queue!(self.stdout_handle, SetForegroundColor(Color::DarkGrey))?;
+            print_fitted(
+                &mut self.stdout_handle,
+                &format!(" (+{})", hidden_right),
+                &mut remaining,
             )?;
         }
 
-        execute!(
+        queue!(
             self.stdout_handle,
             ResetColor,
-            Clear(ClearType::UntilNewLine),
-            RestorePosition
+            Clear(ClearType::UntilNewLine)
         )?;
-
-        self.hints_rendered = true;
         Ok(())
     }
 
@@ -865,7 +2338,6 @@ impl TerminalApp {
     /// Clears the input line and completions before returning true to signal exit.
     pub async fn handle_ctrl_d(&mut self) -> Result<bool, Box<dyn std::error::Error>> {
         self.current_input.clear();
-        self.cursor_position = 0;
         self.current_completions.clear();
         self.clear_input_line();
         Ok(true)
@@ -882,7 +2354,6 @@ impl TerminalApp {
         self.current_completions.clear();
         if !self.current_input.is_empty() {
             self.current_input.clear();
-            self.cursor_position = 0;
             self.last_ctrl_c = Some(Instant::now());
             return Ok((
                 false,
@@ -913,7 +2384,6 @@ impl TerminalApp {
         // Save current input if we're at the end (no history selected yet)
         if self.history_index.is_none() {
             self.pending_input = Some(self.current_input.clone());
-            self.pending_cursor_position = self.cursor_position;
         }
 
         let new_index = match self.history_index {
@@ -922,8 +2392,10 @@ impl TerminalApp {
             None => self.command_history.len() - 1,
         };
         self.history_index = Some(new_index);
-        self.current_input = self.command_history[new_index].clone();
-        self.cursor_position = self.current_input.chars().count();
+        self.current_input
+            .set_text(self.command_history[new_index].as_str());
+        // Undo steps belong to the line they were made on
+        self.undo_stack.clear();
         self.update_completions();
     }
 
@@ -936,25 +2408,96 @@ impl TerminalApp {
                 // Restore pending input if available, otherwise clear
                 if let Some(pending) = self.pending_input.take() {
                     self.current_input = pending;
-                    self.cursor_position = self.pending_cursor_position;
                 } else {
                     self.current_input.clear();
-                    self.cursor_position = 0;
                 }
+                self.undo_stack.clear();
                 self.update_completions();
                 return;
             }
             None => return,
         };
         self.history_index = Some(new_index);
-        self.current_input = self.command_history[new_index].clone();
-        self.cursor_position = self.current_input.chars().count();
+        self.current_input
+            .set_text(self.command_history[new_index].as_str());
+        // Undo steps belong to the line they were made on
+        self.undo_stack.clear();
+        self.update_completions();
+    }
+
+    /// Starts a Ctrl+R reverse history search from the current input.
+    fn start_history_search(&mut self) {
+        self.history_search = Some(ReverseSearch::new(
+            self.current_input.as_str().to_string(),
+            self.current_input.char_cursor(),
+        ));
+        self.current_completions.clear();
+    }
+
+    /// Handles a key press while reverse history search is active.
+    ///
+    /// - Typing and `DeleteBackward` edit the search term
+    /// - `ReverseSearch` (Ctrl+R) steps to the next older match
+    /// - `Submit` (Enter) accepts the match into the input line for further editing
+    /// - Esc, `Abort` (Ctrl+G) or `Interrupt` (Ctrl+C) restore the original input
+    /// - Any other key accepts the match and is then processed normally
+    ///
+    /// Returns whether the key was consumed by the search.
+    fn handle_search_key(
+        &mut self,
+        key_event: &KeyEvent,
+        action: Option<EditorAction>,
+    ) -> Result<bool, Box<dyn std::error::Error>> {
+        let Some(search) = &mut self.history_search else {
+            return Ok(false);
+        };
+
+        match (action, key_event.code) {
+            (Some(EditorAction::ReverseSearch), _) => search.search_older(&self.command_history),
+            (Some(EditorAction::Abort | EditorAction::Interrupt), _) | (_, KeyCode::Esc) => {
+                self.finish_history_search(false)
+            }
+            (Some(EditorAction::Submit), _) => {
+                self.with_undo(EditKind::Other, |app| app.finish_history_search(true))
+            }
+            (Some(EditorAction::DeleteBackward), _) => search.pop_char(&self.command_history),
+            (None, KeyCode::Char(c)) if is_text_modifiers(key_event.modifiers) => {
+                search.push_char(c, &self.command_history)
+            }
+            _ => {
+                self.with_undo(EditKind::Other, |app| app.finish_history_search(true));
+                return Ok(false);
+            }
+        }
+
+        self.render_input_line()?;
+        Ok(true)
+    }
+
+    /// Ends the reverse history search.
+    ///
+    /// If `accept` is true and an entry matched, it replaces the input line.
+    /// Otherwise, the input from before the search is restored.
+    fn finish_history_search(&mut self, accept: bool) {
+        let Some(search) = self.history_search.take() else {
+            return;
+        };
+        match search.matched(&self.command_history) {
+            Some(matched) if accept => {
+                self.current_input.set_text(matched);
+            }
+            _ => {
+                self.current_input.set_text(search.original_input);
+                self.current_input.set_char_cursor(search.original_cursor);
+            }
+        }
+        self.history_index = None;
         self.update_completions();
     }
 
     /// Handles Enter key press to submit input.
     ///
-    /// If input is non-empty, adds it to history, echoes it with the prefix,
+    /// If input is non-empty, adds it to history (and the history file, if set), echoes it with the prefix,
     /// clears the input state, and returns the input string. If empty, just
     /// clears and re-renders the input line.
     ///
@@ -963,27 +2506,305 @@ impl TerminalApp {
         &mut self,
         input_prefix: &str,
     ) -> Result<(bool, Option<String>), Box<dyn std::error::Error>> {
-        if !self.current_input.trim().is_empty() {
-            self.command_history.push(self.current_input.clone());
+        if !self.current_input.as_str().trim().is_empty() {
+            self.push_history(self.current_input.as_str().to_string());
             self.current_completions.clear();
-            self.clear_input_line();
-            writeln!(self.stdout_handle, "{}{}", input_prefix, self.current_input)?;
 
-            let input_copy = self.current_input.clone();
-            self.current_input.clear();
-            self.cursor_position = 0;
+            let input_copy = self.current_input.take();
             self.history_index = None;
-            self.render_input_line()?;
+            self.undo_stack.clear();
+            // Echo the submitted input like a log entry
+            let continuation = format!("\n{}", self.continuation_prompt.render());
+            let echo = input_copy.replace('\n', &continuation);
+            self.print_log_entry(&format!("{}{}", input_prefix, echo));
+            if self.fixed_layout_active {
+                // The fixed layout leaves the input area alone when logging
+                self.render_input_line()?;
+            }
 
             Ok((self.should_exit, Some(input_copy)))
         } else {
             self.current_completions.clear();
-            self.clear_input_line();
             self.render_input_line()?;
             Ok((self.should_exit, None))
         }
     }
 
+    /// Applies a readline-style editing action to the input line.
+    ///
+    /// Consecutive kills are joined into one kill ring entry, and `YankPop` only
+    /// works right after a yank. Actions that are not about editing are ignored.
+    fn apply_edit_action(&mut self, action: EditorAction, last_edit: LastEdit) {
+        let continues_kill = matches!(last_edit, LastEdit::Kill);
+        let cursor = self.current_input.char_cursor();
+        // Start and end of the current line, which is the whole input unless it is multi-line
+        let (line_start, line_end) = multiline::line_bounds(self.current_input.as_str(), cursor);
+
+        match action {
+            EditorAction::MoveToStart => self.current_input.set_char_cursor(line_start),
+            EditorAction::MoveToEnd => {
+                if !self.accept_suggestion(true) {
+                    self.current_input.set_char_cursor(line_end)
+                }
+            }
+
+            EditorAction::MoveWordLeft => {
+                self.current_input
+                    .set_char_cursor(editor::word_start_before(
+                        self.current_input.as_str(),
+                        cursor,
+                    ))
+            }
+            EditorAction::MoveWordRight => {
+                if !self.accept_suggestion(false) {
+                    self.current_input.set_char_cursor(editor::word_end_after(
+                        self.current_input.as_str(),
+                        cursor,
+                    ))
+                }
+            }
+            EditorAction::DeleteBackward => {
+                self.current_input.delete_backward();
+            }
+            EditorAction::DeleteForward => self.delete_char_forward(),
+            EditorAction::UnixWordRubout => {
+                let start = editor::big_word_start_before(self.current_input.as_str(), cursor);
+                self.kill_range(start, cursor, KillDirection::Backward, continues_kill);
+            }
+            EditorAction::KillWordBackward => {
+                let start = editor::word_start_before(self.current_input.as_str(), cursor);
+                self.kill_range(start, cursor, KillDirection::Backward, continues_kill);
+            }
+            EditorAction::KillWordForward => {
+                let end = editor::word_end_after(self.current_input.as_str(), cursor);
+                self.kill_range(cursor, end, KillDirection::Forward, continues_kill);
+            }
+            EditorAction::KillToStart => {
+                self.kill_range(line_start, cursor, KillDirection::Backward, continues_kill)
+            }
+            EditorAction::KillToEnd => {
+                self.kill_range(cursor, line_end, KillDirection::Forward, continues_kill)
+            }
+            EditorAction::Yank => {
+                if let Some(text) = self.kill_ring.yank().map(str::to_string) {
+                    self.insert_yank(&text);
+                }
+            }
+            EditorAction::YankPop => {
+                // Yank-pop only makes sense right after a yank
+                let LastEdit::Yank { start, len } = last_edit else {
+                    return;
+                };
+                if let Some(text) = self.kill_ring.rotate().map(str::to_string) {
+                    self.remove_chars(start, start + len);
+                    self.current_input.set_char_cursor(start);
+                    self.insert_yank(&text);
+                }
+            }
+            EditorAction::TransposeChars => self.transpose_chars(),
+            _ => return,
+        }
+
+        self.history_index = None;
+    }
+
+    /// Handles a key press in vi mode.
+    ///
+    /// In insert mode only Esc is intercepted, switching to normal mode. In normal
+    /// mode, plain characters are vi commands and Esc cancels a pending operator.
+    /// Everything else falls through to the keymap.
+    ///
+    /// Returns whether the key was consumed.
+    fn handle_vi_key(&mut self, key_event: &KeyEvent) -> Result<bool, Box<dyn std::error::Error>> {
+        let plain_esc = key_event.code == KeyCode::Esc && key_event.modifiers == KeyModifiers::NONE;
+
+        match self.vi.mode {
+            ViMode::Insert if plain_esc => {
+                // Like vim, leaving insert mode moves the cursor onto the last typed character
+                self.vi.enter_normal();
+                self.undo_stack.break_group();
+                let before = &self.current_input.as_str()[..self.current_input.cursor()];
+                if !before.ends_with('\n') {
+                    self.current_input.move_left();
+                }
+            }
+            ViMode::Insert => return Ok(false),
+            ViMode::Normal if plain_esc => {
+                self.vi.cancel_pending();
+            }
+            ViMode::Normal
+                if key_event.code == KeyCode::Char('r')
+                    && key_event.modifiers == KeyModifiers::CONTROL =>
+            {
+                // Ctrl+R is redo in vi normal mode, as in vim
+                self.redo_edit();
+            }
+            ViMode::Normal => {
+                let KeyCode::Char(c) = key_event.code else {
+                    return Ok(false);
+                };
+                if !is_text_modifiers(key_event.modifiers) {
+                    return Ok(false);
+                }
+                self.last_edit = LastEdit::Other;
+                let command = self.vi.handle_key(
+                    c,
+                    self.current_input.as_str(),
+                    self.current_input.char_cursor(),
+                );
+                if command == ViCommand::Undo {
+                    self.undo_edit();
+                } else {
+                    self.with_undo(EditKind::Other, |app| app.apply_vi_command(command));
+                }
+            }
+        }
+
+        self.clamp_vi_cursor();
+        self.render_input_line()?;
+        Ok(true)
+    }
+
+    /// Keeps the vi normal mode cursor on a character, never past the end of its line.
+    fn clamp_vi_cursor(&mut self) {
+        if self.edit_mode != EditMode::Vi || self.vi.mode != ViMode::Normal {
+            return;
+        }
+        let text = self.current_input.as_str();
+        let cursor = self.current_input.cursor();
+        let at_line_end = cursor == text.len() || text[cursor..].starts_with('\n');
+        let empty_line = cursor == 0 || text[..cursor].ends_with('\n');
+        if at_line_end && !empty_line {
+            self.current_input.move_left();
+        }
+    }
+
+    /// Applies a command produced by the vi normal mode.
+    fn apply_vi_command(&mut self, command: ViCommand) {
+        match command {
+            ViCommand::None => return,
+            ViCommand::Move(position) => self.current_input.set_char_cursor(position),
+            ViCommand::Delete { start, end, insert } => {
+                self.kill_range(start, end, KillDirection::Forward, false);
+                if insert {
+                    self.vi.enter_insert();
+                }
+            }
+            ViCommand::Insert(position) => {
+                self.current_input.set_char_cursor(position);
+                self.vi.enter_insert();
+            }
+            ViCommand::Put => {
+                if let Some(text) = self.kill_ring.yank().map(str::to_string) {
+                    self.current_input.move_right();
+                    self.insert_yank(&text);
+                    // Leave the cursor on the last pasted character
+                    self.current_input.move_left();
+                }
+            }
+            ViCommand::HistoryPrevious => self.handle_up_key(),
+            ViCommand::HistoryNext => self.handle_down_key(),
+            ViCommand::Undo => self.undo_edit(),
+        }
+        self.update_completions();
+    }
+
+    /// Runs an edit and records it on the undo stack if it changed the input.
+    ///
+    /// Edits that only move the cursor end the current undo group instead. The input
+    /// is only copied when the edit may start a new undo step.
+    fn with_undo(&mut self, kind: EditKind, edit: impl FnOnce(&mut Self)) {
+        let revision = self.current_input.revision();
+        let cursor = self.current_input.cursor();
+        let before = (!self.undo_stack.extends_group(kind)).then(|| self.input_snapshot());
+        edit(self);
+        if self.current_input.revision() != revision {
+            // Without a snapshot, the change is part of the open group already
+            if let Some(before) = before {
+                self.undo_stack.record(before, kind);
+            }
+        } else if self.current_input.cursor() != cursor {
+            self.undo_stack.break_group();
+        }
+    }
+
+    /// Returns the input line state for the undo stack.
+    fn input_snapshot(&self) -> Snapshot {
+        Snapshot {
+            text: self.current_input.as_str().to_string(),
+            cursor: self.current_input.char_cursor(),
+        }
+    }
+
+    /// Undoes the last change to the input line.
+    fn undo_edit(&mut self) {
+        let current = self.input_snapshot();
+        if let Some(snapshot) = self.undo_stack.undo(current) {
+            self.restore_snapshot(snapshot);
+        }
+    }
+
+    /// Redoes the last undone change to the input line.
+    fn redo_edit(&mut self) {
+        let current = self.input_snapshot();
+        if let Some(snapshot) = self.undo_stack.redo(current) {
+            self.restore_snapshot(snapshot);
+        }
+    }
+
+    /// Replaces the input line with a saved snapshot.
+    fn restore_snapshot(&mut self, snapshot: Snapshot) {
+        self.current_input.set_text(snapshot.text);
+        self.current_input.set_char_cursor(snapshot.cursor);
+        self.history_index = None;
+        self.update_completions();
+    }
+
+    /// Removes `start..end` from the input and records it in the kill ring.
+    fn kill_range(&mut self, start: usize, end: usize, direction: KillDirection, append: bool) {
+        if start < end {
+            let killed = self.remove_chars(start, end);
+            self.kill_ring.kill(&killed, direction, append);
+            self.current_input.set_char_cursor(start);
+        }
+        // Keep joining kills even if this one was empty, like readline
+        self.last_edit = LastEdit::Kill;
+    }
+
+    /// Inserts yanked text at the cursor and remembers it for yank-pop.
+    fn insert_yank(&mut self, text: &str) {
+        let start = self.current_input.char_cursor();
+        self.insert_at_cursor(text);
+        self.last_edit = LastEdit::Yank {
+            start,
+            len: text.chars().count(),
+        };
+    }
+
+    /// Inserts text at the cursor and moves the cursor after it.
+    fn insert_at_cursor(&mut self, text: &str) {
+        self.current_input.insert_str(text);
+    }
+
+    /// Removes the characters in `start..end` from the input and returns them.
+    fn remove_chars(&mut self, start: usize, end: usize) -> String {
+        let start = grapheme::byte_offset(self.current_input.as_str(), start);
+        let end = grapheme::byte_offset(self.current_input.as_str(), end);
+        self.current_input.drain(start..end)
+    }
+
+    /// Deletes the character (grapheme cluster) under the cursor.
+    fn delete_char_forward(&mut self) {
+        self.current_input.delete_forward();
+    }
+
+    /// Swaps the characters around the cursor, or the last two at end of line.
+    ///
+    /// Characters are grapheme clusters, so an emoji or accented letter moves as a whole.
+    fn transpose_chars(&mut self) {
+        self.current_input.transpose();
+    }
+
     /// Handles Tab key press to apply the selected completion.
     ///
     /// If a completion is selected (via Left/Right arrows), uses that completion.
@@ -992,42 +2813,106 @@ impl TerminalApp {
         if !self.current_completions.is_empty()
             && self.selected_completion_index < self.current_completions.len()
         {
-            self.current_input = self.current_completions[self.selected_completion_index]
-                .full_text
-                .clone();
-            self.cursor_position = self.current_input.chars().count();
+            self.current_input.set_text(
+                self.current_completions[self.selected_completion_index]
+                    .full_text
+                    .as_str(),
+            );
             self.update_completions();
         } else if let Some(tree) = &mut self.tab_tree
-            && let Some(completion) = tree.get_best_match(&self.current_input)
+            && let Some(completion) = tree.get_best_match(self.current_input.as_str())
         {
-            self.current_input = completion;
-            self.cursor_position = self.current_input.chars().count();
+            self.current_input.set_text(completion);
             self.update_completions();
         }
     }
 
+    /// Inserts a line break at the cursor.
+    fn insert_newline(&mut self) {
+        self.handle_char_input('\n');
+        self.history_index = None;
+    }
+
+    /// Moves the cursor to the same column of the line above or below.
+    ///
+    /// Returns false if there is no such line, so the caller can fall back to
+    /// history navigation.
+    fn move_to_adjacent_line(&mut self, down: bool) -> bool {
+        let (line, col) = multiline::line_col(
+            self.current_input.as_str(),
+            self.current_input.char_cursor(),
+        );
+        let target = if down {
+            if line + 1 >= multiline::line_count(self.current_input.as_str()) {
+                return false;
+            }
+            line + 1
+        } else {
+            let Some(above) = line.checked_sub(1) else {
+                return false;
+            };
+            above
+        };
+        let position = multiline::position_of(self.current_input.as_str(), target, col);
+        self.current_input.set_char_cursor(position);
+        self.undo_stack.break_group();
+        true
+    }
+
+    /// Returns the autosuggestion for the current input, if any.
+    ///
+    /// Suggestions are only shown with the cursor at the end of the input.
+    fn current_suggestion(&self) -> Option<String> {
+        if self.autosuggest_mode == AutosuggestMode::Off
+            || self.history_search.is_some()
+            || self.current_input.cursor() < self.current_input.len()
+        {
+            return None;
+        }
+        let input = self.current_input.as_str();
+        if let Some(rest) = suggest::suggest_from_history(&self.command_history, input) {
+            return Some(rest.to_string());
+        }
+        if self.autosuggest_mode != AutosuggestMode::HistoryAndCompletions || input.is_empty() {
+            return None;
+        }
+        self.current_completions
+            .first()
+            .and_then(|candidate| candidate.full_text.strip_prefix(input))
+            .filter(|rest| !rest.is_empty())
+            .map(str::to_string)
+    }
+
+    /// Inserts the autosuggestion, or only its next word, at the end of the input.
+    ///
+    /// Returns false if there was no suggestion to accept.
+    fn accept_suggestion(&mut self, whole: bool) -> bool {
+        let Some(suggestion) = self.current_suggestion() else {
+            return false;
+        };
+        let accepted = if whole {
+            suggestion.as_str()
+        } else {
+            suggest::next_suggested_word(&suggestion)
+        };
+        self.current_input.insert_str(accepted);
+        self.history_index = None;
+        true
+    }
+
     /// Updates completion candidates based on current input.
     ///
     /// Resets the selected completion index to 0 when candidates change.
     fn update_completions(&mut self) {
         if let Some(tree) = &mut self.tab_tree {
-            self.current_completions = tree.get_candidates(&self.current_input);
+            self.current_completions = tree.get_candidates(self.current_input.as_str());
             self.selected_completion_index = 0;
         }
     }
 
     /// Handles character input by inserting at the cursor position.
     fn handle_char_input(&mut self, c: char) {
-        let char_count = self.current_input.chars().count();
-
-        if self.cursor_position > char_count {
-            self.cursor_position = char_count;
-        }
-
-        let mut chars: Vec<char> = self.current_input.chars().collect();
-        chars.insert(self.cursor_position, c);
-        self.current_input = chars.into_iter().collect();
-        self.cursor_position += 1;
+        self.current_input.insert_char(c);
     }
 
     /// Log info-level messages.
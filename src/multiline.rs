@@ -0,0 +1,124 @@
+//! Multi-line input.
+//!
+//! With multi-line input enabled, Enter on an incomplete input inserts a line break
+//! instead of submitting it. A continuation predicate decides when the input is
+//! incomplete; `is_incomplete` is the default one. Lines and columns are counted in
+//! characters, and a position in the whole input counts each line break as one.
+
+/// Decides whether the input so far is incomplete and Enter should start a new line.
+pub type ContinuationPredicate = Box<dyn Fn(&str) -> bool + Send + Sync>;
+
+/// Default continuation predicate: an unclosed bracket or a trailing backslash.
+///
+/// `(`, `[` and `{` inside double-quoted strings don't count, so JSON works as
+/// expected. Single quotes are left alone since they are mostly apostrophes.
+///
+/// # Examples
+///
+/// ```
+/// use daemon_console_lite::multiline::is_incomplete;
+///
+/// assert!(is_incomplete("config set {"));
+/// assert!(is_incomplete("run build \\"));
+/// assert!(is_incomplete("{\"items\": [1, 2,"));
+/// assert!(!is_incomplete("{\"name\": \"a { b\"}"));
+/// assert!(!is_incomplete("version"));
+/// ```
+pub fn is_incomplete(input: &str) -> bool {
+    if input.trim_end().ends_with('\\') {
+        return true;
+    }
+
+    let mut depth = 0i32;
+    let mut in_string = false;
+    let mut escaped = false;
+    for c in input.chars() {
+        if in_string {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == '"' {
+                in_string = false;
+            }
+            continue;
+        }
+        match c {
+            '"' => in_string = true,
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => depth -= 1,
+            _ => {}
+        }
+    }
+    depth > 0
+}
+
+/// Returns the number of lines in the input.
+pub fn line_count(text: &str) -> usize {
+    text.split('\n').count()
+}
+
+/// Returns the line and column of a cursor position, both as character indices.
+///
+/// # Examples
+///
+/// ```
+/// use daemon_console_lite::multiline::line_col;
+///
+/// let text = "{\n  \"port\": 80\n}";
+/// assert_eq!(line_col(text, 0), (0, 0));
+/// assert_eq!(line_col(text, 4), (1, 2));
+/// assert_eq!(line_col(text, 16), (2, 1));
+/// ```
+pub fn line_col(text: &str, cursor: usize) -> (usize, usize) {
+    let mut line = 0;
+    let mut col = 0;
+    for c in text.chars().take(cursor) {
+        if c == '\n' {
+            line += 1;
+            col = 0;
+        } else {
+            col += 1;
+        }
+    }
+    (line, col)
+}
+
+/// Returns the start and end of the line the cursor is on.
+///
+/// # Examples
+///
+/// ```
+/// use daemon_console_lite::multiline::line_bounds;
+///
+/// let text = "config\nset port";
+/// assert_eq!(line_bounds(text, 3), (0, 6));
+/// assert_eq!(line_bounds(text, 10), (7, 15));
+/// ```
+pub fn line_bounds(text: &str, cursor: usize) -> (usize, usize) {
+    let (line, _) = line_col(text, cursor);
+    (
+        position_of(text, line, 0),
+        position_of(text, line, usize::MAX),
+    )
+}
+
+/// Returns the cursor position of a line and column, clamping the column to the
+/// length of the line and the line to the last one.
+///
+/// # Examples
+///
+/// ```
+/// use daemon_console_lite::multiline::position_of;
+///
+/// let text = "{\n  \"port\": 80\n}";
+/// assert_eq!(position_of(text, 1, 2), 4);
+/// // Column past the end of the last line
+/// assert_eq!(position_of(text, 2, 10), 16);
+/// ```
+pub fn position_of(text: &str, line: usize, col: usize) -> usize {
+    let lines: Vec<&str> = text.split('\n').collect();
+    let line = line.min(lines.len() - 1);
+    let start: usize = lines[..line].iter().map(|l| l.chars().count() + 1).sum();
+    start + col.min(lines[line].chars().count())
+}
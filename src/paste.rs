@@ -0,0 +1,43 @@
+//! Bracketed paste.
+//!
+//! With bracketed paste enabled, the terminal delivers pasted text as a single
+//! `Event::Paste` instead of one key event per character, so an embedded newline no
+//! longer submits a half-pasted command. `PasteMode` decides what those newlines do.
+
+/// What newlines in pasted text do.
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Default)]
+pub enum PasteMode {
+    /// Keep the pasted lines together as multi-line input, submitted with Enter (default)
+    #[default]
+    Buffer,
+    /// Submit every complete pasted line as if Enter was pressed after it; text
+    /// after the last newline stays in the input
+    Submit,
+}
+
+/// Cleans up pasted text for the input line.
+///
+/// Line endings become `\n`, tabs become four spaces and other control characters
+/// are dropped, since they have no width on screen.
+///
+/// # Examples
+///
+/// ```
+/// use daemon_console_lite::paste::normalize_paste;
+///
+/// assert_eq!(normalize_paste("config start\r\nconfig stop\r"), "config start\nconfig stop\n");
+/// assert_eq!(normalize_paste("a\tb\x07"), "a    b");
+/// ```
+pub fn normalize_paste(text: &str) -> String {
+    let text = text.replace("\r\n", "\n").replace('\r', "\n");
+    let mut normalized = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\n' => normalized.push('\n'),
+            '\t' => normalized.push_str("    "),
+            c if c.is_control() => {}
+            c => normalized.push(c),
+        }
+    }
+    normalized
+}
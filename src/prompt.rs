@@ -0,0 +1,129 @@
+//! Styled, runtime-changeable input prompt.
+//!
+//! A `Prompt` is a list of segments, each with optional color and bold styling. The
+//! display width is computed from the segment text, so styling and wide (e.g. CJK)
+//! characters don't throw off cursor placement.
+
+use crossterm::style::{Attribute, Color, ResetColor, SetForegroundColor};
+use unicode_width::UnicodeWidthStr;
+
+/// A piece of prompt text with its style.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PromptSegment {
+    /// Text of the segment
+    pub text: String,
+    /// Foreground color, or the terminal default
+    pub color: Option<Color>,
+    /// Whether the text is bold
+    pub bold: bool,
+}
+
+/// Prompt shown in front of the input line.
+///
+/// # Examples
+///
+/// ```
+/// use crossterm::style::Color;
+/// use daemon_console_lite::prompt::Prompt;
+///
+/// let prompt = Prompt::new()
+///     .colored("控制台", Color::Cyan)
+///     .styled(" [maintenance]", Some(Color::Yellow), true)
+///     .text("> ");
+///
+/// assert_eq!(prompt.plain_text(), "控制台 [maintenance]> ");
+/// // CJK characters are two columns wide
+/// assert_eq!(prompt.width(), 6 + 14 + 2);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Prompt {
+    segments: Vec<PromptSegment>,
+}
+
+impl Prompt {
+    /// Creates an empty prompt.
+    pub fn new() -> Self {
+        Self {
+            segments: Vec::new(),
+        }
+    }
+
+    /// Appends a segment with the given style.
+    pub fn styled(mut self, text: impl Into<String>, color: Option<Color>, bold: bool) -> Self {
+        self.segments.push(PromptSegment {
+            text: text.into(),
+            color,
+            bold,
+        });
+        self
+    }
+
+    /// Appends unstyled text.
+    pub fn text(self, text: impl Into<String>) -> Self {
+        self.styled(text, None, false)
+    }
+
+    /// Appends colored text.
+    pub fn colored(self, text: impl Into<String>, color: Color) -> Self {
+        self.styled(text, Some(color), false)
+    }
+
+    /// Appends bold text.
+    pub fn bold(self, text: impl Into<String>) -> Self {
+        self.styled(text, None, true)
+    }
+
+    /// Returns the segments of the prompt.
+    pub fn segments(&self) -> &[PromptSegment] {
+        &self.segments
+    }
+
+    /// Returns the prompt text without styling.
+    pub fn plain_text(&self) -> String {
+        self.segments.iter().map(|s| s.text.as_str()).collect()
+    }
+
+    /// Returns the number of terminal columns the prompt occupies.
+    pub fn width(&self) -> usize {
+        self.segments.iter().map(|s| s.text.width()).sum()
+    }
+
+    /// Returns the prompt with ANSI styling, ready to print.
+    pub fn render(&self) -> String {
+        let mut rendered = String::new();
+        for segment in &self.segments {
+            let styled = segment.color.is_some() || segment.bold;
+            if let Some(color) = segment.color {
+                rendered.push_str(&SetForegroundColor(color).to_string());
+            }
+            if segment.bold {
+                rendered.push_str(&Attribute::Bold.to_string());
+            }
+            rendered.push_str(&segment.text);
+            if styled {
+                rendered.push_str(&Attribute::Reset.to_string());
+                rendered.push_str(&ResetColor.to_string());
+            }
+        }
+        rendered
+    }
+}
+
+impl Default for Prompt {
+    /// The classic `"> "` prompt.
+    fn default() -> Self {
+        Self::new().text("> ")
+    }
+}
+
+impl From<&str> for Prompt {
+    fn from(text: &str) -> Self {
+        Self::new().text(text)
+    }
+}
+
+impl From<String> for Prompt {
+    fn from(text: String) -> Self {
+        Self::new().text(text)
+    }
+}
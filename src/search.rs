@@ -0,0 +1,134 @@
+//! Reverse incremental history search (Ctrl+R).
+//!
+//! Works like readline's `reverse-search-history`: typing narrows the search term,
+//! repeated Ctrl+R steps to older matches, and aborting restores the original input.
+
+/// State of an active reverse-i-search session.
+#[derive(Clone, Debug)]
+pub struct ReverseSearch {
+    /// Text being searched for
+    pub term: String,
+    /// Index in the history of the current match
+    pub match_index: Option<usize>,
+    /// Whether the last search step found nothing
+    pub failed: bool,
+    /// Input line before the search started, restored on abort
+    pub original_input: String,
+    /// Cursor position before the search started
+    pub original_cursor: usize,
+}
+
+impl ReverseSearch {
+    /// Starts a new search session, remembering the input to restore on abort.
+    pub fn new(original_input: String, original_cursor: usize) -> Self {
+        Self {
+            term: String::new(),
+            match_index: None,
+            failed: false,
+            original_input,
+            original_cursor,
+        }
+    }
+
+    /// Finds the newest entry before `before` that contains the term.
+    fn find(&self, history: &[String], before: usize) -> Option<usize> {
+        history[..before.min(history.len())]
+            .iter()
+            .rposition(|entry| entry.contains(&self.term))
+    }
+
+    /// Searches again from the current match (inclusive) after the term changed.
+    fn refresh(&mut self, history: &[String]) {
+        let start = self.match_index.map_or(history.len(), |idx| idx + 1);
+        match self.find(history, start) {
+            Some(idx) => {
+                self.match_index = Some(idx);
+                self.failed = false;
+            }
+            None => self.failed = true,
+        }
+    }
+
+    /// Appends a character to the term and updates the match.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daemon_console_lite::search::ReverseSearch;
+    ///
+    /// let history = vec!["config set port 80".to_string(), "version".to_string()];
+    /// let mut search = ReverseSearch::new(String::new(), 0);
+    /// search.push_char('p', &history);
+    /// search.push_char('o', &history);
+    /// assert_eq!(search.matched(&history), Some("config set port 80"));
+    /// ```
+    pub fn push_char(&mut self, c: char, history: &[String]) {
+        self.term.push(c);
+        self.refresh(history);
+    }
+
+    /// Removes the last character of the term and searches again from the newest entry.
+    pub fn pop_char(&mut self, history: &[String]) {
+        self.term.pop();
+        self.match_index = None;
+        self.failed = false;
+        if !self.term.is_empty() {
+            self.refresh(history);
+        }
+    }
+
+    /// Steps to the next older entry matching the term.
+    ///
+    /// Stays on the current match and marks the search as failed if there is none.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daemon_console_lite::search::ReverseSearch;
+    ///
+    /// let history = vec![
+    ///     "config set host a".to_string(),
+    ///     "version".to_string(),
+    ///     "config set port 80".to_string(),
+    /// ];
+    /// let mut search = ReverseSearch::new(String::new(), 0);
+    /// for c in "set".chars() {
+    ///     search.push_char(c, &history);
+    /// }
+    /// assert_eq!(search.match_index, Some(2));
+    /// search.search_older(&history);
+    /// assert_eq!(search.match_index, Some(0));
+    /// search.search_older(&history);
+    /// assert!(search.failed);
+    /// assert_eq!(search.match_index, Some(0));
+    /// ```
+    pub fn search_older(&mut self, history: &[String]) {
+        if self.term.is_empty() {
+            return;
+        }
+        let before = self.match_index.unwrap_or(history.len());
+        match self.find(history, before) {
+            Some(idx) => {
+                self.match_index = Some(idx);
+                self.failed = false;
+            }
+            None => self.failed = true,
+        }
+    }
+
+    /// Returns the currently matched history entry, if any.
+    pub fn matched<'a>(&self, history: &'a [String]) -> Option<&'a str> {
+        self.match_index
+            .and_then(|idx| history.get(idx))
+            .map(String::as_str)
+    }
+
+    /// Returns the prompt shown while searching, e.g. `(reverse-i-search)'conf': `.
+    pub fn prompt(&self) -> String {
+        if self.failed {
+            format!("(failed reverse-i-search)'{}': ", self.term)
+        } else {
+            format!("(reverse-i-search)'{}': ", self.term)
+        }
+    }
+}
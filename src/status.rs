@@ -0,0 +1,161 @@
+//! One-line status bar drawn next to the input line.
+//!
+//! The bar is made of named segments, e.g. `uptime` or `clients`, shown left to right
+//! in the order they were first set. Segments are updated by name, either directly on
+//! `TerminalApp` or from other tasks through `ConsoleHandle::set_status()`.
+
+use crossterm::style::{Color, ResetColor, SetForegroundColor};
+use unicode_width::UnicodeWidthStr;
+
+use crate::utils::truncate_to_width;
+
+/// Text placed between segments.
+const SEPARATOR: &str = " | ";
+
+/// Where the status bar is drawn relative to the input line.
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Default)]
+pub enum StatusPosition {
+    /// Directly above the input line
+    Above,
+    /// Below the input line and completion hints (default)
+    #[default]
+    Below,
+}
+
+/// A named piece of the status bar.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StatusSegment {
+    /// Name used to update or remove the segment
+    pub name: String,
+    /// Text shown in the bar
+    pub text: String,
+    /// Foreground color, or the terminal default
+    pub color: Option<Color>,
+}
+
+/// Named segments shown in the status bar.
+///
+/// # Examples
+///
+/// ```
+/// use crossterm::style::Color;
+/// use daemon_console_lite::status::StatusBar;
+///
+/// let mut bar = StatusBar::new();
+/// bar.set("uptime", "up 3m", None);
+/// bar.set("clients", "2 clients", Some(Color::Green));
+/// bar.set("uptime", "up 4m", None);
+///
+/// assert_eq!(bar.plain_text(), "up 4m | 2 clients");
+///
+/// bar.remove("uptime");
+/// assert_eq!(bar.plain_text(), "2 clients");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StatusBar {
+    segments: Vec<StatusSegment>,
+}
+
+impl StatusBar {
+    /// Creates an empty status bar.
+    pub fn new() -> Self {
+        Self {
+            segments: Vec::new(),
+        }
+    }
+
+    /// Sets the text of a segment, adding it at the end if it doesn't exist yet.
+    pub fn set(&mut self, name: &str, text: impl Into<String>, color: Option<Color>) {
+        let text = text.into();
+        match self.segments.iter_mut().find(|s| s.name == name) {
+            Some(segment) => {
+                segment.text = text;
+                segment.color = color;
+            }
+            None => self.segments.push(StatusSegment {
+                name: name.to_string(),
+                text,
+                color,
+            }),
+        }
+    }
+
+    /// Removes a segment, returning whether it existed.
+    pub fn remove(&mut self, name: &str) -> bool {
+        let len = self.segments.len();
+        self.segments.retain(|s| s.name != name);
+        self.segments.len() != len
+    }
+
+    /// Removes all segments.
+    pub fn clear(&mut self) {
+        self.segments.clear();
+    }
+
+    /// Returns a segment by name.
+    pub fn get(&self, name: &str) -> Option<&StatusSegment> {
+        self.segments.iter().find(|s| s.name == name)
+    }
+
+    /// Returns all segments in display order.
+    pub fn segments(&self) -> &[StatusSegment] {
+        &self.segments
+    }
+
+    /// Returns whether the bar has no segments, in which case it is not drawn.
+    pub fn is_empty(&self) -> bool {
+        self.segments.is_empty()
+    }
+
+    /// Returns the bar text without styling.
+    pub fn plain_text(&self) -> String {
+        self.segments
+            .iter()
+            .map(|s| s.text.as_str())
+            .collect::<Vec<_>>()
+            .join(SEPARATOR)
+    }
+
+    /// Returns the bar with ANSI styling, cut to fit in `width` columns.
+    pub fn render(&self, width: usize) -> String {
+        let mut rendered = String::new();
+        let mut remaining = width;
+
+        for (idx, segment) in self.segments.iter().enumerate() {
+            if idx > 0 {
+                let separator = fit_width(SEPARATOR, &mut remaining);
+                rendered.push_str(&SetForegroundColor(Color::DarkGrey).to_string());
+                rendered.push_str(separator);
+                rendered.push_str(&ResetColor.to_string());
+            }
+            if let Some(color) = segment.color {
+                rendered.push_str(&SetForegroundColor(color).to_string());
+            }
+            rendered.push_str(fit_width(&segment.text, &mut remaining));
+            if segment.color.is_some() {
+                rendered.push_str(&ResetColor.to_string());
+            }
+            if remaining == 0 {
+                break;
+            }
+        }
+        rendered
+    }
+}
+
+impl Default for StatusBar {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Takes as many leading characters of `text` as fit in `remaining` columns.
+fn fit_width<'a>(text: &'a str, remaining: &mut usize) -> &'a str {
+    let fitted = truncate_to_width(text, *remaining);
+    if fitted.len() < text.len() {
+        *remaining = 0;
+    } else {
+        *remaining -= fitted.width();
+    }
+    fitted
+}
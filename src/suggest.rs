@@ -0,0 +1,64 @@
+//! Fish-style autosuggestions.
+//!
+//! While typing, the rest of the newest history entry starting with the input is
+//! shown greyed out after the cursor. Right, End or Ctrl+F at the end of the input
+//! accepts all of it, Alt+F accepts it word by word.
+
+use crate::{editor, grapheme};
+
+/// Where autosuggestions come from.
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Default)]
+pub enum AutosuggestMode {
+    /// No autosuggestions (default)
+    #[default]
+    Off,
+    /// The newest matching history entry
+    History,
+    /// The newest matching history entry, or the best tab completion if no entry
+    /// matches
+    HistoryAndCompletions,
+}
+
+/// Returns the rest of the newest history entry that starts with `input`.
+///
+/// Nothing is suggested for an empty input or when an entry only matches exactly.
+///
+/// # Examples
+///
+/// ```
+/// use daemon_console_lite::suggest::suggest_from_history;
+///
+/// let history = vec!["config set port 80".to_string(), "config get port".to_string()];
+/// assert_eq!(suggest_from_history(&history, "config s"), Some("et port 80"));
+/// // The newest match wins
+/// assert_eq!(suggest_from_history(&history, "config"), Some(" get port"));
+/// assert_eq!(suggest_from_history(&history, "version"), None);
+/// assert_eq!(suggest_from_history(&history, ""), None);
+/// ```
+pub fn suggest_from_history<'a>(history: &'a [String], input: &str) -> Option<&'a str> {
+    if input.is_empty() {
+        return None;
+    }
+    history
+        .iter()
+        .rev()
+        .filter_map(|entry| entry.strip_prefix(input))
+        .find(|rest| !rest.is_empty())
+}
+
+/// Returns the part of a suggestion accepted by one Alt+F: up to the end of its
+/// next word.
+///
+/// # Examples
+///
+/// ```
+/// use daemon_console_lite::suggest::next_suggested_word;
+///
+/// assert_eq!(next_suggested_word("et port 80"), "et");
+/// assert_eq!(next_suggested_word(" port 80"), " port");
+/// assert_eq!(next_suggested_word(" 80"), " 80");
+/// ```
+pub fn next_suggested_word(suggestion: &str) -> &str {
+    let end = editor::word_end_after(suggestion, 0);
+    &suggestion[..grapheme::byte_offset(suggestion, end)]
+}
@@ -218,6 +218,51 @@ impl TabTree {
         }
     }
 
+    /// Removes a completion item from a context, returning whether it was there.
+    ///
+    /// # Arguments
+    ///
+    /// * `context` - The context it was registered in (empty string for root)
+    /// * `text` - Completion text
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daemon_console_lite::tab::TabTree;
+    ///
+    /// let mut tree = TabTree::new();
+    /// tree.register_completions("config", &["start", "stop"]);
+    /// assert!(tree.remove_completion("config", "stop"));
+    /// assert!(!tree.is_known("config", "stop"));
+    /// assert!(!tree.remove_completion("config", "stop"));
+    /// ```
+    pub fn remove_completion(&mut self, context: &str, text: &str) -> bool {
+        fn find_node_mut<'a>(node: &'a mut TabNode, trigger: &str) -> Option<&'a mut TabNode> {
+            if node.trigger.as_deref() == Some(trigger) {
+                return Some(node);
+            }
+            node.children
+                .iter_mut()
+                .find_map(|child| find_node_mut(child, trigger))
+        }
+
+        let node = if context.is_empty() {
+            Some(&mut self.root)
+        } else {
+            find_node_mut(&mut self.root, context)
+        };
+        let Some(node) = node else {
+            return false;
+        };
+        let count = node.completions.len();
+        node.completions.retain(|item| item.text != text);
+        let removed = node.completions.len() != count;
+        if removed {
+            self.clear_cache();
+        }
+        removed
+    }
+
     /// Finds or creates a node with the given trigger.
     fn find_or_create_node(&mut self, trigger: Option<&str>) -> Option<&mut TabNode> {
         if let Some(trigger_str) = trigger {
@@ -339,7 +384,7 @@ impl TabTree {
         }
 
         // Sort by priority (higher first)
-        candidates.sort_by(|a, b| b.priority.cmp(&a.priority));
+        candidates.sort_by_key(|item| std::cmp::Reverse(item.priority));
 
         // Build completion candidates
         let trigger_prefix = node.trigger.as_deref().unwrap_or("");
@@ -376,6 +421,52 @@ impl TabTree {
         self.current_candidates.clear();
     }
 
+    /// Checks whether `word` is a completion registered for `context`.
+    ///
+    /// The context must match a registered one exactly (empty for the root), and
+    /// surrounding whitespace on both sides is ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `context` - The words before `word`, as registered
+    /// * `word` - The word to look up
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daemon_console_lite::tab::TabTree;
+    ///
+    /// let mut tree = TabTree::new();
+    /// tree.register_completions("", &["config", "version"]);
+    /// tree.register_completions("config", &["start", "stop"]);
+    ///
+    /// assert!(tree.is_known("", "config"));
+    /// assert!(tree.is_known("config", "start"));
+    /// assert!(!tree.is_known("", "start"));
+    /// assert!(!tree.is_known("version", "start"));
+    /// ```
+    pub fn is_known(&self, context: &str, word: &str) -> bool {
+        fn find_node<'a>(node: &'a TabNode, trigger: &str) -> Option<&'a TabNode> {
+            if node.trigger.as_deref().map(str::trim) == Some(trigger) {
+                return Some(node);
+            }
+            node.children
+                .iter()
+                .find_map(|child| find_node(child, trigger))
+        }
+
+        let context = context.trim();
+        let node = if context.is_empty() {
+            Some(&self.root)
+        } else {
+            find_node(&self.root, context)
+        };
+        let word = word.trim();
+        node.is_some_and(|node| {
+            !word.is_empty() && node.completions.iter().any(|item| item.text.trim() == word)
+        })
+    }
+
     /// Counts the total number of completion items in the tree.
     pub fn count_total_items(&self) -> usize {
         fn count_node(node: &TabNode) -> usize {
@@ -0,0 +1,142 @@
+//! Undo and redo for the input line.
+//!
+//! The stack stores snapshots of the input taken before each change. Consecutive
+//! changes of the same groupable kind (typing, or deleting character by character)
+//! collapse into a single undo step, so one undo removes a whole typed word run
+//! instead of one character.
+
+/// Default maximum number of undo steps kept.
+const DEFAULT_MAX_STEPS: usize = 100;
+
+/// Input line state saved by the undo stack.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snapshot {
+    /// Input text
+    pub text: String,
+    /// Cursor position as a character index
+    pub cursor: usize,
+}
+
+/// Kind of change, used to decide which changes are grouped together.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum EditKind {
+    /// Typed characters, grouped with directly preceding typing
+    Insert,
+    /// Single character deletions, grouped with directly preceding deletions
+    Delete,
+    /// Any other change, e.g. a kill or an applied completion, always its own step
+    Other,
+}
+
+/// Undo/redo history of the input line.
+///
+/// # Examples
+///
+/// ```
+/// use daemon_console_lite::undo::{EditKind, Snapshot, UndoStack};
+///
+/// let snap = |text: &str| Snapshot { text: text.to_string(), cursor: text.chars().count() };
+/// let mut undo = UndoStack::new();
+///
+/// // Typing "hi" is one step, applying a completion is another
+/// undo.record(snap(""), EditKind::Insert);
+/// undo.record(snap("h"), EditKind::Insert);
+/// undo.record(snap("hi"), EditKind::Other);
+///
+/// assert_eq!(undo.undo(snap("hi there")), Some(snap("hi")));
+/// assert_eq!(undo.undo(snap("hi")), Some(snap("")));
+/// assert_eq!(undo.undo(snap("")), None);
+/// assert_eq!(undo.redo(snap("")), Some(snap("hi")));
+/// ```
+#[derive(Debug, Clone)]
+pub struct UndoStack {
+    undo: Vec<Snapshot>,
+    redo: Vec<Snapshot>,
+    /// Kind of the last recorded change while it can still be extended
+    open_group: Option<EditKind>,
+    max_steps: usize,
+}
+
+impl UndoStack {
+    /// Creates an empty undo stack.
+    pub fn new() -> Self {
+        Self {
+            undo: Vec::new(),
+            redo: Vec::new(),
+            open_group: None,
+            max_steps: DEFAULT_MAX_STEPS,
+        }
+    }
+
+    /// Returns whether a change of the given kind joins the current undo step.
+    ///
+    /// Such a change needs no snapshot: `record` would drop it anyway.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daemon_console_lite::undo::{EditKind, Snapshot, UndoStack};
+    ///
+    /// let mut undo = UndoStack::new();
+    /// assert!(!undo.extends_group(EditKind::Insert));
+    /// undo.record(Snapshot { text: String::new(), cursor: 0 }, EditKind::Insert);
+    /// assert!(undo.extends_group(EditKind::Insert));
+    /// assert!(!undo.extends_group(EditKind::Delete));
+    /// ```
+    pub fn extends_group(&self, kind: EditKind) -> bool {
+        kind != EditKind::Other && self.open_group == Some(kind)
+    }
+
+    /// Records the state from before a change of the given kind.
+    ///
+    /// Any redo steps are discarded, since the change starts a new branch.
+    pub fn record(&mut self, before: Snapshot, kind: EditKind) {
+        self.redo.clear();
+        if !self.extends_group(kind) {
+            self.undo.push(before);
+            if self.undo.len() > self.max_steps {
+                self.undo.remove(0);
+            }
+        }
+        self.open_group = match kind {
+            EditKind::Other => None,
+            kind => Some(kind),
+        };
+    }
+
+    /// Ends the current group, e.g. when the cursor moves between two runs of typing.
+    pub fn break_group(&mut self) {
+        self.open_group = None;
+    }
+
+    /// Steps back, returning the state to restore.
+    ///
+    /// `current` is saved so the step can be redone.
+    pub fn undo(&mut self, current: Snapshot) -> Option<Snapshot> {
+        let previous = self.undo.pop()?;
+        self.redo.push(current);
+        self.open_group = None;
+        Some(previous)
+    }
+
+    /// Re-applies the last undone step, returning the state to restore.
+    pub fn redo(&mut self, current: Snapshot) -> Option<Snapshot> {
+        let next = self.redo.pop()?;
+        self.undo.push(current);
+        self.open_group = None;
+        Some(next)
+    }
+
+    /// Forgets all steps, e.g. when a different line is loaded.
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+        self.open_group = None;
+    }
+}
+
+impl Default for UndoStack {
+    fn default() -> Self {
+        Self::new()
+    }
+}
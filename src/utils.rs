@@ -1,4 +1,6 @@
 use chrono::{Local, TimeZone};
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
 
 pub fn get_local_timestring(time: i64) -> String {
     let datetime = Local
@@ -12,3 +14,26 @@ pub fn get_local_timestring(time: i64) -> String {
         });
     datetime.format("%H:%M:%S").to_string()
 }
+
+/// Returns the longest prefix of `text` that fits in `width` terminal columns.
+///
+/// # Examples
+///
+/// ```
+/// use daemon_console_lite::utils::truncate_to_width;
+///
+/// assert_eq!(truncate_to_width("config set", 6), "config");
+/// // Wide characters and emoji sequences are never cut in half
+/// assert_eq!(truncate_to_width("控制台", 5), "控制");
+/// assert_eq!(truncate_to_width("ok \u{1F468}\u{200D}\u{1F469}", 4), "ok ");
+/// ```
+pub fn truncate_to_width(text: &str, width: usize) -> &str {
+    let mut used = 0;
+    for (idx, g) in text.grapheme_indices(true) {
+        used += g.width();
+        if used > width {
+            return &text[..idx];
+        }
+    }
+    text
+}
@@ -0,0 +1,359 @@
+//! Optional vi editing mode.
+//!
+//! In vi mode the line editor starts in insert mode, where keys behave as usual.
+//! Esc switches to normal mode, where single keys are motions (`h l w b e 0 ^ $`),
+//! operators (`d c` followed by a motion, `dd`, `cc`) and commands (`x D C p u i a I A`).
+//! `ViState` only decides what a key means; `TerminalApp` applies the result to its
+//! input line.
+
+use crate::{grapheme, multiline};
+
+/// Which editing style the line editor uses.
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Default)]
+pub enum EditMode {
+    /// Readline-style bindings from the keymap (default)
+    #[default]
+    Emacs,
+    /// Modal vi-style editing on top of the keymap
+    Vi,
+}
+
+/// Current vi sub-mode.
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Default)]
+pub enum ViMode {
+    /// Typing inserts text
+    #[default]
+    Insert,
+    /// Keys are motions, operators and commands
+    Normal,
+}
+
+impl ViMode {
+    /// Returns the mode indicator shown in front of the prompt.
+    pub fn indicator(self) -> &'static str {
+        match self {
+            ViMode::Insert => "[I] ",
+            ViMode::Normal => "[N] ",
+        }
+    }
+}
+
+/// What a key pressed in normal mode asks the editor to do.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum ViCommand {
+    /// Nothing to do, e.g. the first key of an operator like `d`
+    None,
+    /// Move the cursor to a character index
+    Move(usize),
+    /// Kill the characters in `start..end`, then enter insert mode if `insert` is true
+    Delete {
+        start: usize,
+        end: usize,
+        insert: bool,
+    },
+    /// Enter insert mode with the cursor at a character index
+    Insert(usize),
+    /// Insert the most recent kill after the cursor
+    Put,
+    /// Recall the previous history entry (`k`)
+    HistoryPrevious,
+    /// Recall the next history entry (`j`)
+    HistoryNext,
+    /// Undo the last change (`u`)
+    Undo,
+}
+
+/// Character classes used by vi word motions.
+#[derive(PartialEq, Eq, Copy, Clone)]
+enum CharClass {
+    Whitespace,
+    Word,
+    Punctuation,
+}
+
+fn char_class(c: char) -> CharClass {
+    if c.is_whitespace() {
+        CharClass::Whitespace
+    } else if c.is_alphanumeric() || c == '_' {
+        CharClass::Word
+    } else {
+        CharClass::Punctuation
+    }
+}
+
+/// Finds the start of the next word (`w`).
+///
+/// # Examples
+///
+/// ```
+/// use daemon_console_lite::vi::next_word_start;
+///
+/// assert_eq!(next_word_start("config set-port 80", 0), 7);
+/// assert_eq!(next_word_start("config set-port 80", 7), 10);
+/// assert_eq!(next_word_start("config", 2), 6);
+/// ```
+pub fn next_word_start(text: &str, cursor: usize) -> usize {
+    let chars = grapheme::lead_chars(text);
+    let mut pos = cursor.min(chars.len());
+    if pos < chars.len() {
+        let class = char_class(chars[pos]);
+        if class != CharClass::Whitespace {
+            while pos < chars.len() && char_class(chars[pos]) == class {
+                pos += 1;
+            }
+        }
+    }
+    while pos < chars.len() && char_class(chars[pos]) == CharClass::Whitespace {
+        pos += 1;
+    }
+    pos
+}
+
+/// Finds the start of the current or previous word (`b`).
+///
+/// # Examples
+///
+/// ```
+/// use daemon_console_lite::vi::prev_word_start;
+///
+/// assert_eq!(prev_word_start("config set-port", 15), 11);
+/// assert_eq!(prev_word_start("config set-port", 7), 0);
+/// ```
+pub fn prev_word_start(text: &str, cursor: usize) -> usize {
+    let chars = grapheme::lead_chars(text);
+    let mut pos = cursor.min(chars.len());
+    while pos > 0 && char_class(chars[pos - 1]) == CharClass::Whitespace {
+        pos -= 1;
+    }
+    if pos > 0 {
+        let class = char_class(chars[pos - 1]);
+        while pos > 0 && char_class(chars[pos - 1]) == class {
+            pos -= 1;
+        }
+    }
+    pos
+}
+
+/// Finds the last character of the current or next word (`e`).
+///
+/// # Examples
+///
+/// ```
+/// use daemon_console_lite::vi::word_end;
+///
+/// assert_eq!(word_end("config set", 0), 5);
+/// assert_eq!(word_end("config set", 5), 9);
+/// ```
+pub fn word_end(text: &str, cursor: usize) -> usize {
+    let chars = grapheme::lead_chars(text);
+    if chars.is_empty() {
+        return 0;
+    }
+    let mut pos = grapheme::next_boundary(text, cursor);
+    while pos < chars.len() && char_class(chars[pos]) == CharClass::Whitespace {
+        pos += 1;
+    }
+    if pos >= chars.len() {
+        return grapheme::prev_boundary(text, chars.len());
+    }
+    let class = char_class(chars[pos]);
+    while pos + 1 < chars.len() && char_class(chars[pos + 1]) == class {
+        pos += 1;
+    }
+    grapheme::snap_to_boundary(text, pos)
+}
+
+/// Finds the first non-blank character of the line (`^`), or its last character
+/// if it is all blank.
+///
+/// # Examples
+///
+/// ```
+/// use daemon_console_lite::vi::first_non_blank;
+///
+/// assert_eq!(first_non_blank("  config set"), 2);
+/// assert_eq!(first_non_blank("   "), 2);
+/// assert_eq!(first_non_blank(""), 0);
+/// ```
+pub fn first_non_blank(text: &str) -> usize {
+    match text.chars().position(|c| !c.is_whitespace()) {
+        Some(pos) => pos,
+        None => grapheme::prev_boundary(text, text.chars().count()),
+    }
+}
+
+/// Finds the first non-blank character of the line in `start..end` of `text`.
+fn line_first_non_blank(text: &str, start: usize, end: usize) -> usize {
+    let line: String = text.chars().skip(start).take(end - start).collect();
+    start + first_non_blank(&line)
+}
+
+/// State of the vi line editor.
+///
+/// # Examples
+///
+/// ```
+/// use daemon_console_lite::vi::{ViCommand, ViState};
+///
+/// let mut vi = ViState::default();
+/// vi.enter_normal();
+///
+/// // `^` skips the leading blanks, `0` doesn't
+/// assert_eq!(vi.handle_key('^', "  config", 5), ViCommand::Move(2));
+/// assert_eq!(vi.handle_key('0', "  config", 5), ViCommand::Move(0));
+///
+/// // `dw` deletes up to the start of the next word
+/// assert_eq!(vi.handle_key('d', "config set port", 0), ViCommand::None);
+/// assert_eq!(
+///     vi.handle_key('w', "config set port", 0),
+///     ViCommand::Delete { start: 0, end: 7, insert: false }
+/// );
+///
+/// // `cw` changes to the end of the word, keeping the following space
+/// vi.handle_key('c', "config set port", 7);
+/// assert_eq!(
+///     vi.handle_key('w', "config set port", 7),
+///     ViCommand::Delete { start: 7, end: 10, insert: true }
+/// );
+///
+/// // On multi-line input, line motions and `dd` stay on the cursor's line
+/// let text = "config\nset port";
+/// assert_eq!(vi.handle_key('0', text, 10), ViCommand::Move(7));
+/// assert_eq!(vi.handle_key('$', text, 3), ViCommand::Move(5));
+/// assert_eq!(
+///     vi.handle_key('D', text, 10),
+///     ViCommand::Delete { start: 10, end: 15, insert: false }
+/// );
+/// vi.handle_key('d', text, 3);
+/// assert_eq!(
+///     vi.handle_key('d', text, 3),
+///     ViCommand::Delete { start: 0, end: 7, insert: false }
+/// );
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct ViState {
+    /// Current sub-mode
+    pub mode: ViMode,
+    /// Operator (`d` or `c`) waiting for its motion
+    pending_operator: Option<char>,
+}
+
+impl ViState {
+    /// Switches to normal mode, dropping any pending operator.
+    pub fn enter_normal(&mut self) {
+        self.mode = ViMode::Normal;
+        self.pending_operator = None;
+    }
+
+    /// Switches to insert mode.
+    pub fn enter_insert(&mut self) {
+        self.mode = ViMode::Insert;
+        self.pending_operator = None;
+    }
+
+    /// Cancels a pending operator, returning whether there was one.
+    pub fn cancel_pending(&mut self) -> bool {
+        self.pending_operator.take().is_some()
+    }
+
+    /// Interprets a character typed in normal mode.
+    ///
+    /// `text` is the input and `cursor` the cursor position as a character index.
+    /// Line motions like `0`, `$` and `D` work on the line the cursor is on.
+    pub fn handle_key(&mut self, key: char, text: &str, cursor: usize) -> ViCommand {
+        let len = text.chars().count();
+        // Start of the last cluster, where the normal mode cursor stops
+        let last = grapheme::prev_boundary(text, len);
+        let (line_start, line_end) = multiline::line_bounds(text, cursor);
+        let line_last = grapheme::prev_boundary(text, line_end).max(line_start);
+
+        if let Some(operator) = self.pending_operator.take() {
+            return self.apply_operator(operator, key, text, cursor);
+        }
+
+        match key {
+            'h' => ViCommand::Move(grapheme::prev_boundary(text, cursor)),
+            'l' => ViCommand::Move(grapheme::next_boundary(text, cursor).min(line_last)),
+            'w' => ViCommand::Move(next_word_start(text, cursor).min(last)),
+            'b' => ViCommand::Move(prev_word_start(text, cursor)),
+            'e' => ViCommand::Move(word_end(text, cursor)),
+            '0' => ViCommand::Move(line_start),
+            '^' => ViCommand::Move(line_first_non_blank(text, line_start, line_end)),
+            '$' => ViCommand::Move(line_last),
+            'x' if len > 0 => ViCommand::Delete {
+                start: cursor,
+                end: grapheme::next_boundary(text, cursor),
+                insert: false,
+            },
+            'D' => ViCommand::Delete {
+                start: cursor,
+                end: line_end,
+                insert: false,
+            },
+            'C' => ViCommand::Delete {
+                start: cursor,
+                end: line_end,
+                insert: true,
+            },
+            'd' | 'c' => {
+                self.pending_operator = Some(key);
+                ViCommand::None
+            }
+            'i' => ViCommand::Insert(cursor),
+            'a' => ViCommand::Insert(grapheme::next_boundary(text, cursor)),
+            'I' => ViCommand::Insert(line_start),
+            'A' => ViCommand::Insert(line_end),
+            'p' => ViCommand::Put,
+            'k' => ViCommand::HistoryPrevious,
+            'j' => ViCommand::HistoryNext,
+            'u' => ViCommand::Undo,
+            _ => ViCommand::None,
+        }
+    }
+
+    /// Resolves `operator` followed by a motion key into a deletion.
+    fn apply_operator(&self, operator: char, motion: char, text: &str, cursor: usize) -> ViCommand {
+        let len = text.chars().count();
+        let insert = operator == 'c';
+        let (line_start, line_end) = multiline::line_bounds(text, cursor);
+
+        let (start, end) = match motion {
+            // `cc` empties the line, `dd` removes it along with a line break
+            m if m == operator && insert => (line_start, line_end),
+            m if m == operator && line_end < len => (line_start, line_end + 1),
+            m if m == operator => (line_start.saturating_sub(1), line_end),
+            'w' if insert => {
+                // Like vim, `cw` on a word behaves like `ce`
+                let chars = grapheme::lead_chars(text);
+                match chars.get(cursor) {
+                    Some(&c) if !c.is_whitespace() => {
+                        let class = char_class(c);
+                        let mut end = cursor + 1;
+                        while end < len && char_class(chars[end]) == class {
+                            end += 1;
+                        }
+                        (cursor, end)
+                    }
+                    _ => (cursor, next_word_start(text, cursor)),
+                }
+            }
+            'w' => (cursor, next_word_start(text, cursor)),
+            'e' => (
+                cursor,
+                grapheme::next_boundary(text, word_end(text, cursor)),
+            ),
+            'b' => (prev_word_start(text, cursor), cursor),
+            'h' => (grapheme::prev_boundary(text, cursor), cursor),
+            'l' => (cursor, grapheme::next_boundary(text, cursor)),
+            '0' => (line_start, cursor),
+            '^' => {
+                let first = line_first_non_blank(text, line_start, line_end);
+                (first.min(cursor), first.max(cursor))
+            }
+            '$' => (cursor, line_end),
+            _ => return ViCommand::None,
+        };
+
+        ViCommand::Delete { start, end, insert }
+    }
+}
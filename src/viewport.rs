@@ -0,0 +1,172 @@
+//! Horizontal scrolling of a single input line.
+//!
+//! When the input is wider than the space next to the prompt, only a window of it is
+//! shown. The window follows the cursor, and `<` / `>` markers show that text is
+//! hidden on that side. Widths are terminal columns, so wide (e.g. CJK) characters
+//! take two, and grapheme clusters like emoji sequences are never cut in half.
+
+use crate::grapheme;
+
+/// The part of an input line that fits on screen.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InputView {
+    /// Character index of the first visible character
+    pub offset: usize,
+    /// Character index after the last visible character
+    pub end: usize,
+    /// Whether text is hidden on the left, shown as a `<` marker
+    pub hidden_left: bool,
+    /// Whether text is hidden on the right, shown as a `>` marker
+    pub hidden_right: bool,
+    /// Cursor column relative to the start of the view, markers included
+    pub cursor_column: usize,
+}
+
+impl InputView {
+    /// Returns the visible text, without markers.
+    pub fn visible<'a>(&self, text: &'a str) -> &'a str {
+        let start = text
+            .char_indices()
+            .nth(self.offset)
+            .map_or(text.len(), |(i, _)| i);
+        let end = text
+            .char_indices()
+            .nth(self.end)
+            .map_or(text.len(), |(i, _)| i);
+        &text[start..end]
+    }
+}
+
+/// Fits `text` into `width` columns around the cursor.
+///
+/// `cursor` is a character index and `offset` the first visible character of the
+/// previous view, so the window only moves when the cursor would leave it. Returns
+/// the whole text when it fits, leaving room for the cursor after the last character.
+///
+/// # Examples
+///
+/// ```
+/// use daemon_console_lite::viewport::scroll_input;
+///
+/// // Fits: nothing hidden
+/// let view = scroll_input("config", 6, 0, 10);
+/// assert_eq!((view.offset, view.end, view.cursor_column), (0, 6, 6));
+///
+/// // A ZWJ emoji sequence is five characters but two columns
+/// let view = scroll_input("a\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}", 6, 0, 10);
+/// assert_eq!((view.end, view.cursor_column), (6, 3));
+///
+/// // Cursor at the end of a long line: the start is hidden behind `<`
+/// let text = "config set host example.org";
+/// let view = scroll_input(text, 27, 0, 10);
+/// assert!(view.hidden_left && !view.hidden_right);
+/// assert_eq!(view.visible(text), "mple.org");
+/// assert_eq!(view.cursor_column, 9);
+///
+/// // Moving back to the start scrolls left again
+/// let view = scroll_input(text, 0, view.offset, 10);
+/// assert!(!view.hidden_left && view.hidden_right);
+/// assert_eq!(view.visible(text), "config se");
+/// ```
+pub fn scroll_input(text: &str, cursor: usize, offset: usize, width: usize) -> InputView {
+    // Lay out whole grapheme clusters, then map back to character indices
+    let clusters = grapheme::clusters(text);
+    let widths: Vec<usize> = clusters.iter().map(|&(_, width)| width).collect();
+    let mut starts = vec![0];
+    for &(chars, _) in &clusters {
+        starts.push(starts[starts.len() - 1] + chars);
+    }
+    let to_cluster = |char_index: usize| starts.iter().rposition(|&start| start <= char_index);
+    let len = widths.len();
+    let cursor = to_cluster(cursor).unwrap_or(0);
+    let offset = to_cluster(offset).unwrap_or(0);
+
+    let view = if widths.iter().sum::<usize>() < width {
+        InputView {
+            offset: 0,
+            end: len,
+            hidden_left: false,
+            hidden_right: false,
+            cursor_column: widths[..cursor].iter().sum(),
+        }
+    } else {
+        scroll_clusters(&widths, cursor, offset, width)
+    };
+    InputView {
+        offset: starts[view.offset],
+        end: starts[view.end],
+        ..view
+    }
+}
+
+/// Scrolls the clusters of a line that doesn't fit. Indices are cluster indices.
+fn scroll_clusters(widths: &[usize], cursor: usize, offset: usize, width: usize) -> InputView {
+    let len = widths.len();
+
+    // Scroll right until the cursor is on screen
+    let mut offset = offset.min(cursor);
+    let mut view = layout(widths, offset, cursor, width);
+    while view.is_none() && offset < cursor {
+        offset += 1;
+        view = layout(widths, offset, cursor, width);
+    }
+    // Scroll left while that shows more text without losing the cursor
+    while let Some(current) = &view
+        && current.end == len
+        && offset > 0
+    {
+        match layout(widths, offset - 1, cursor, width) {
+            Some(wider) if wider.end == len => {
+                offset -= 1;
+                view = Some(wider);
+            }
+            _ => break,
+        }
+    }
+
+    view.unwrap_or(InputView {
+        offset: cursor,
+        end: cursor,
+        hidden_left: cursor > 0,
+        hidden_right: cursor < len,
+        cursor_column: 0,
+    })
+}
+
+/// Lays out the clusters starting at `offset`, or returns `None` if the cursor
+/// doesn't fit on screen.
+fn layout(widths: &[usize], offset: usize, cursor: usize, width: usize) -> Option<InputView> {
+    let hidden_left = offset > 0;
+    let mut available = width.saturating_sub(usize::from(hidden_left));
+
+    let fit = |available: usize| {
+        let mut used = 0;
+        let mut end = offset;
+        while end < widths.len() && used + widths[end] <= available {
+            used += widths[end];
+            end += 1;
+        }
+        (end, used)
+    };
+
+    let (mut end, mut used) = fit(available);
+    let hidden_right = end < widths.len();
+    if hidden_right {
+        available = available.saturating_sub(1);
+        (end, used) = fit(available);
+    }
+
+    // The cursor needs a column of its own when it sits after the last character
+    let cursor_visible = if cursor == widths.len() {
+        end == widths.len() && used < available
+    } else {
+        cursor < end
+    };
+    cursor_visible.then(|| InputView {
+        offset,
+        end,
+        hidden_left,
+        hidden_right,
+        cursor_column: usize::from(hidden_left) + widths[offset..cursor].iter().sum::<usize>(),
+    })
+}
@@ -1,122 +1,206 @@
 /// This module is used for testing only.
-use daemon_console_lite::TerminalApp;
+use crossterm::style::Color;
+use daemon_console_lite::command::{Arg, Command};
+use daemon_console_lite::keymap::EditorAction;
+use daemon_console_lite::layout::LayoutMode;
+use daemon_console_lite::suggest::AutosuggestMode;
+use daemon_console_lite::{Prompt, TerminalApp};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+fn app_prompt(app_name: &str) -> Prompt {
+    Prompt::new().colored(app_name, Color::Cyan).text("> ")
+}
 
-fn handle_input(app: &mut TerminalApp, input: &str, node_counter: &mut usize) -> bool {
-    match input {
-        "version" => {
-            app.info("Demo - v1");
-            false
-        }
-        "exit" => {
-            app.info("Exiting...");
-            true
-        }
-        input if input.starts_with("app set-name ") => {
-            let parts: Vec<&str> = input.split_whitespace().collect();
-            if parts.len() != 3 {
-                app.info("Usage: app set-name <name>");
-                return false;
-            }
-            let new_name = parts[2];
-            app.app_name = new_name.to_string();
-            app.info(&format!("App name set to: {}", new_name));
-            false
-        }
-        input if input.starts_with("config start") => {
-            app.info("Starting service...");
-            false
-        }
-        input if input.starts_with("config stop") => {
-            app.info("Stopping service...");
-            false
-        }
-        input if input.starts_with("config restart") => {
-            app.info("Restarting service...");
-            false
-        }
-        input if input.starts_with("config set port") => {
-            app.info("Setting port configuration...");
-            false
-        }
-        input if input.starts_with("config set host") => {
-            app.info("Setting host configuration...");
-            false
-        }
+/// Declares the demo commands, which also fills in their tab completions.
+fn register_commands(app: &mut TerminalApp) {
+    app.register_command(
+        Command::new("version")
+            .description("Show the version.")
+            .handler(|app, _| app.info("Demo - v1")),
+    );
+    app.register_command(
+        Command::new("exit")
+            .description("Exit the demo.")
+            .handler(|app, _| {
+                app.info("Exiting...");
+                app.should_exit = true;
+            }),
+    );
+    app.register_command(
+        Command::new("app")
+            .description("Manage the application.")
+            .subcommand(
+                Command::new("set-name")
+                    .description("Set the name of the application.")
+                    .arg(Arg::new("name"))
+                    .handler(|app, args| {
+                        let new_name = args.string("name").unwrap_or_default().to_string();
+                        app.app_name = new_name.clone();
+                        app.set_prompt(app_prompt(&app.app_name));
+                        app.info(&format!("App name set to: {}", new_name));
+                    }),
+            ),
+    );
+    app.register_command(
+        Command::new("config")
+            .description("Manage the service.")
+            .subcommand(Command::new("start").handler(|app, _| app.info("Starting service...")))
+            .subcommand(Command::new("stop").handler(|app, _| app.info("Stopping service...")))
+            .subcommand(Command::new("restart").handler(|app, _| app.info("Restarting service...")))
+            .subcommand(Command::new("status").handler(|app, _| app.info("Service is running.")))
+            .subcommand(
+                Command::new("set")
+                    .subcommand(
+                        Command::new("port")
+                            .description("Set port number.")
+                            .arg(Arg::int("port"))
+                            .handler(|app, args| {
+                                let port = args.int("port").unwrap_or_default();
+                                app.info(&format!("Setting port to {}...", port));
+                            }),
+                    )
+                    .subcommand(
+                        Command::new("host")
+                            .description("Set host address.")
+                            .arg(Arg::host_port("address"))
+                            .handler(|app, args| {
+                                let (host, port) = args.host_port("address").unwrap_or_default();
+                                app.info(&format!("Setting host to {} on port {}...", host, port));
+                            }),
+                    )
+                    .subcommand(
+                        Command::new("timeout")
+                            .description("Set timeout.")
+                            .arg(Arg::duration("timeout"))
+                            .example("config set timeout 1m30s")
+                            .handler(|app, args| {
+                                let timeout = args.duration("timeout").unwrap_or_default();
+                                app.info(&format!("Setting timeout to {:?}...", timeout));
+                            }),
+                    )
+                    .subcommand(
+                        Command::new("log-level")
+                            .description("Set log verbosity.")
+                            .arg(Arg::choice("level", &["debug", "info", "warn", "error"]))
+                            .handler(|app, args| {
+                                let level = args.string("level").unwrap_or_default();
+                                app.info(&format!("Setting log level to {}...", level));
+                            }),
+                    )
+                    .subcommand(
+                        Command::new("ratio")
+                            .description("Set load ratio.")
+                            .arg(Arg::float("ratio"))
+                            .handler(|app, args| {
+                                let ratio = args.float("ratio").unwrap_or_default();
+                                app.info(&format!("Setting load ratio to {}...", ratio));
+                            }),
+                    ),
+            ),
+    );
 
-        // ----------------------------------------------------
-        // add-node <int> —— Use node_counter
-        // ----------------------------------------------------
-        input if input.starts_with("add-node ") => {
-            let parts: Vec<&str> = input.split_whitespace().collect();
-            if parts.len() != 2 {
-                app.info("Usage: add-node <number>");
-                return false;
-            }
-
-            let Ok(count) = parts[1].parse::<usize>() else {
-                app.info("add-node 参数必须是整数");
-                return false;
-            };
-
-            let start = *node_counter + 1;
-            let end = *node_counter + count;
-
-            let new_nodes: Vec<String> = (start..=end).map(|i| format!("node{}", i)).collect();
-            let ref_nodes: Vec<&str> = new_nodes.iter().map(|s| s.as_str()).collect();
-
-            app.register_tab_completions("", &ref_nodes);
-            *node_counter = end;
-            app.info(&format!("Added nodes node{} to node{}.", start, end));
-            false
-        }
+    app.register_command(
+        Command::new("echo")
+            .description("Print a message.")
+            .arg(Arg::rest("message"))
+            .example("echo Hello, world!")
+            .handler(|app, args| app.info(args.string("message").unwrap_or_default())),
+    );
 
-        _ => {
-            app.info(&format!("You entered: {}", input));
-            false
-        }
-    }
+    // Runs as a job: the prompt stays usable, `jobs` lists it and Ctrl+C on an empty
+    // line cancels it
+    app.register_command(
+        Command::new("sleep")
+            .description("Wait in the background.")
+            .arg(Arg::duration("time"))
+            .example("sleep 10s")
+            .async_handler(|handle, args| async move {
+                let time = args.duration("time").unwrap_or_default();
+                handle.info(&format!("Sleeping for {:?}...", time));
+                tokio::time::sleep(time).await;
+                handle.info("Woke up!");
+            }),
+    );
+
+    // add-node <int> adds that many nodeN completions, numbered on from the last call
+    let node_counter = Arc::new(AtomicUsize::new(0));
+    app.register_command(
+        Command::new("add-node")
+            .description("Add node completions.")
+            .arg(Arg::int("count").description("Number of nodes to add"))
+            .example("add-node 3")
+            .handler(move |app, args| {
+                let count = match usize::try_from(args.int("count").unwrap_or_default()) {
+                    Ok(0) => {
+                        app.error("add-node: count must be at least 1");
+                        return;
+                    }
+                    Ok(count) => count,
+                    Err(_) => {
+                        app.error("add-node: count must not be negative");
+                        return;
+                    }
+                };
+
+                let start = node_counter.fetch_add(count, Ordering::Relaxed) + 1;
+                let end = start + count - 1;
+
+                let new_nodes: Vec<String> = (start..=end).map(|i| format!("node{}", i)).collect();
+                let ref_nodes: Vec<&str> = new_nodes.iter().map(|s| s.as_str()).collect();
+
+                app.register_tab_completions("", &ref_nodes);
+                app.info(&format!("Added nodes node{} to node{}.", start, end));
+            }),
+    );
 }
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let mut app = TerminalApp::new();
-    let mut node_counter: usize = 0;
 
     // Configure tab completion display options
     app.tab_option_max_count = 5; // Show up to 5 completion options
     app.tab_option_max_length = 10; // Truncate options longer than 10 characters
 
+    // Keep command history across restarts
+    app.history_file = Some(std::env::temp_dir().join("dcl_test_history"));
+    app.history_max_entries = 500;
+
+    // F2 toggles completion hints too, and Ctrl+X Ctrl+K clears the line
+    app.keymap.bind_str("f2", EditorAction::ToggleCompletions)?;
+    app.keymap
+        .bind_str("ctrl+x ctrl+k", EditorAction::KillToStart)?;
+
+    // Pin the input line to the bottom of the terminal with `--fixed`
+    if std::env::args().any(|arg| arg == "--fixed") {
+        app.layout_mode = LayoutMode::Fixed;
+    }
+
     app.enable_tab_completion();
+    // Unclosed brackets or a trailing backslash continue the input on a new line
+    app.enable_multiline();
+    // Suggest the rest of earlier commands, or of the best completion
+    app.autosuggest_mode = AutosuggestMode::HistoryAndCompletions;
+    // Known commands in green, unknown ones in red
+    app.enable_highlighting();
+    app.set_prompt(app_prompt(&app.app_name));
 
     app.init_terminal("Welcome to Daemon Console Lite!").await?;
     if app.is_tab_completion_enabled() {
         app.info("Tab completion enabled!");
     }
 
-    app.register_tab_completions(
-        "",
-        &["version", "exit", "help", "config", "app", "add-node"],
-    );
-    app.register_tab_completions("config", &["start", "stop", "restart", "status", "set"]);
-    app.register_tab_completions_with_desc(
-        "add-node ",
-        &[
-            ("", "int: Type should be an integer here."),
-            ("", "Type should be an integer here."),
-        ],
-    );
-    app.register_tab_completions_with_desc(
-        "app",
-        &[("set-name ", "Set the name of the application.")],
-    );
-    app.register_tab_completions_with_desc(
-        "config set",
-        &[
-            ("port", "Set port number."),
-            ("host", "Set host address."),
-            ("timeout", "Set timeout."),
-        ],
-    );
+    register_commands(&mut app);
+    app.enable_help();
+    // `alias r config restart` defines aliases at runtime, `;` separates macro commands
+    app.enable_alias_command();
+    app.enable_job_commands();
+    app.define_alias("r", "config restart")?;
+    app.define_alias(
+        "redeploy",
+        "config stop; config set port 8080; config start",
+    )?;
 
     // Register some long completion options to test truncation
     app.register_tab_completions(
@@ -130,9 +214,30 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     app.debug("System initialized");
 
+    // Log from a background task while the main loop waits for input
+    let handle = app.handle();
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(tokio::time::Duration::from_secs(30)).await;
+            handle.debug("Heartbeat from background worker");
+        }
+    });
+
+    // Keep the status bar up to date from another task
+    app.set_status_colored("state", "running", Color::Green);
+    let status_handle = app.handle();
+    tokio::spawn(async move {
+        let started = std::time::Instant::now();
+        loop {
+            let uptime = started.elapsed().as_secs();
+            status_handle.set_status("uptime", format!("up {}m {:02}s", uptime / 60, uptime % 60));
+            tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
+        }
+    });
+
     while let Some(input) = app.read_input().await? {
-        if handle_input(&mut app, &input, &mut node_counter) {
-            break;
+        if !app.dispatch(&input) {
+            app.info(&format!("You entered: {}", input));
         }
     }
 